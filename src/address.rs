@@ -0,0 +1,310 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{AmburError, ErrorCode};
+use crate::family::same_collection;
+use crate::network::ArchwayNetwork;
+use crate::registry::Registry;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ContractRole {
+    Marketplace,
+    Token,
+    Minter,
+    Cw20,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ContractMatch {
+    pub contract_address: String,
+    pub network: Option<ArchwayNetwork>,
+    pub role: Option<ContractRole>,
+    pub collection: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+}
+
+pub fn address_prefix(addr: &str) -> Result<String, AmburError> {
+    let (hrp, _) = bech32::decode(addr).map_err(|err| {
+        AmburError::new(
+            ErrorCode::InvalidAddress,
+            format!("Invalid bech32 address {addr}: {err}"),
+        )
+    })?;
+    Ok(hrp.as_str().to_string())
+}
+
+pub fn validate_address(addr: &str, prefix: &str) -> Result<(), AmburError> {
+    let hrp = address_prefix(addr)?;
+    if hrp != prefix {
+        return Err(AmburError::new(
+            ErrorCode::InvalidAddress,
+            format!("Address {addr} has prefix '{hrp}', expected '{prefix}'"),
+        ));
+    }
+    Ok(())
+}
+
+// Every registration of an address in the registry. The same address can be
+// deployed on more than one network (e.g. a testnet sharing mainnet's address)
+pub fn lookup_address(
+    addr: &str,
+    registry: &Registry,
+) -> Vec<(ArchwayNetwork, ContractRole, Option<String>)> {
+    let mut registrations = vec![];
+    for contract in registry
+        .contracts
+        .iter()
+        .filter(|c| c.contract_address == addr)
+    {
+        registrations.push((contract.network.clone(), ContractRole::Marketplace, None));
+    }
+    for collection in &registry.collections {
+        let addresses = &collection.contract_addresses;
+        for (role, deployments) in [
+            (ContractRole::Token, &addresses.token),
+            (ContractRole::Minter, &addresses.minter),
+        ] {
+            for contract in deployments.iter().filter(|c| c.contract_address == addr) {
+                registrations.push((
+                    contract.network.clone(),
+                    role,
+                    Some(collection.name.clone()),
+                ));
+            }
+        }
+    }
+    registrations
+}
+
+// The registration of an address on `network`, or its only network when it
+// isn't registered there. None when it isn't registered, or when it's
+// registered on several other networks and the network can't be told
+pub fn lookup_address_on(
+    addr: &str,
+    registry: &Registry,
+    network: Option<&ArchwayNetwork>,
+) -> Option<(ArchwayNetwork, ContractRole, Option<String>)> {
+    let registrations = lookup_address(addr, registry);
+    pick_registration(&registrations, network).cloned()
+}
+
+fn pick_registration<'a>(
+    registrations: &'a [(ArchwayNetwork, ContractRole, Option<String>)],
+    network: Option<&ArchwayNetwork>,
+) -> Option<&'a (ArchwayNetwork, ContractRole, Option<String>)> {
+    match network.and_then(|network| registrations.iter().find(|(n, _, _)| n == network)) {
+        Some(registration) => Some(registration),
+        None => match registrations {
+            [registration] => Some(registration),
+            _ => None,
+        },
+    }
+}
+
+fn describe_networks(registrations: &[(ArchwayNetwork, ContractRole, Option<String>)]) -> String {
+    let networks: Vec<String> = registrations
+        .iter()
+        .map(|(network, _, _)| format!("{network:?}"))
+        .collect();
+    networks.join(" and ")
+}
+
+fn check_network_prefix(addr: &str, registry: &Registry) -> Result<(), AmburError> {
+    let prefix = address_prefix(addr)?;
+    if !registry.networks.iter().any(|n| n.bech32_prefix == prefix) {
+        return Err(AmburError::new(
+            ErrorCode::InvalidAddress,
+            format!(
+                "Address {addr} has prefix '{prefix}', which doesn't match any configured network"
+            ),
+        ));
+    }
+    Ok(())
+}
+
+// Validate `addr` and check it against the registry for the tool being called.
+// Registered addresses with the wrong role (or collection) are refused, unknown
+// addresses are allowed through with a warning. Addresses deployed on several
+// networks are matched on `network` when it's known
+pub fn resolve_address(
+    addr: &str,
+    expected_role: ContractRole,
+    nft: Option<&str>,
+    network: Option<&ArchwayNetwork>,
+    registry: &Registry,
+) -> Result<ContractMatch, AmburError> {
+    check_network_prefix(addr, registry)?;
+    let registrations = lookup_address(addr, registry);
+    let Some((first_network, first_role, _)) = registrations.first() else {
+        // cw20 payment tokens aren't Ambur contracts, so they aren't in the registry
+        let warning = (expected_role != ContractRole::Cw20).then(|| {
+            format!(
+                "Address {addr} is not a registered Ambur contract, verify the address and network before signing"
+            )
+        });
+        return Ok(ContractMatch {
+            contract_address: addr.to_string(),
+            network: None,
+            role: None,
+            collection: None,
+            warning,
+        });
+    };
+    let registrations: Vec<_> = registrations
+        .iter()
+        .filter(|(_, role, _)| role == &expected_role)
+        .cloned()
+        .collect();
+    if registrations.is_empty() {
+        return Err(AmburError::new(
+            ErrorCode::AddressMismatch,
+            format!(
+                "Address {addr} is a {first_role:?} contract on {first_network:?}, but this tool expects a {expected_role:?} contract"
+            ),
+        ));
+    }
+    let (network, warning) = match pick_registration(&registrations, network) {
+        Some((network, _, _)) => (Some(network.clone()), None),
+        None => (
+            None,
+            Some(format!(
+                "Address {addr} is registered on {}, specify the network to tell them apart",
+                describe_networks(&registrations)
+            )),
+        ),
+    };
+    let collection = registrations[0].2.clone();
+    if let (Some(nft), Some(name)) = (nft, &collection)
+        && !same_collection(name, nft)
+    {
+        return Err(AmburError::new(
+            ErrorCode::AddressMismatch,
+            format!(
+                "Address {addr} belongs to the {name} collection on {}, not {nft}",
+                describe_networks(&registrations)
+            ),
+        ));
+    }
+    Ok(ContractMatch {
+        contract_address: addr.to_string(),
+        network,
+        role: Some(expected_role),
+        collection,
+        warning,
+    })
+}
+
+// Validate the address of a contract of any kind (e.g. one with a loaded
+// schema), which usually isn't in the registry. Registered addresses keep
+// their network, role and collection
+pub fn resolve_any_address(
+    addr: &str,
+    network: Option<&ArchwayNetwork>,
+    registry: &Registry,
+) -> Result<ContractMatch, AmburError> {
+    check_network_prefix(addr, registry)?;
+    let registrations = lookup_address(addr, registry);
+    let registered = pick_registration(&registrations, network);
+    let warning = (registered.is_none() && !registrations.is_empty()).then(|| {
+        format!(
+            "Address {addr} is registered on {}, specify the network to tell them apart",
+            describe_networks(&registrations)
+        )
+    });
+    Ok(ContractMatch {
+        contract_address: addr.to_string(),
+        network: registered.map(|(network, _, _)| network.clone()),
+        role: registrations.first().map(|(_, role, _)| *role),
+        collection: registrations
+            .first()
+            .and_then(|(_, _, collection)| collection.clone()),
+        warning,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{CONTRACT_MAINNET, TOKEN_ARCHIES_MAINNET};
+    use bech32::{Bech32, Hrp};
+
+    // The same account under another prefix
+    fn with_prefix(addr: &str, prefix: &str) -> String {
+        let (_, data) = bech32::decode(addr).unwrap();
+        bech32::encode::<Bech32>(Hrp::parse(prefix).unwrap(), &data).unwrap()
+    }
+
+    #[test]
+    fn validates_the_prefix() {
+        assert_eq!(address_prefix(CONTRACT_MAINNET).unwrap(), "archway");
+        assert!(validate_address(CONTRACT_MAINNET, "archway").is_ok());
+
+        let cosmos = with_prefix(CONTRACT_MAINNET, "cosmos");
+        assert_eq!(address_prefix(&cosmos).unwrap(), "cosmos");
+        let err = validate_address(&cosmos, "archway").unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidAddress);
+        assert!(err.message.contains("prefix 'cosmos', expected 'archway'"));
+    }
+
+    #[test]
+    fn refuses_invalid_bech32() {
+        let mut bad_checksum = CONTRACT_MAINNET.to_string();
+        let last = bad_checksum.pop().unwrap();
+        bad_checksum.push(if last == 'q' { 'p' } else { 'q' });
+        for addr in [
+            bad_checksum.as_str(),
+            "archway",
+            "",
+            "archway1",
+            "0xdeadbeef",
+        ] {
+            let err = address_prefix(addr).unwrap_err();
+            assert_eq!(err.code, ErrorCode::InvalidAddress, "{addr}");
+        }
+    }
+
+    #[test]
+    fn resolving_checks_the_prefix_against_the_configured_networks() {
+        let registry = Registry::default();
+        let osmo = with_prefix(TOKEN_ARCHIES_MAINNET, "osmo");
+        for result in [
+            resolve_address(&osmo, ContractRole::Token, None, None, &registry),
+            resolve_any_address(&osmo, None, &registry),
+        ] {
+            let err = result.unwrap_err();
+            assert_eq!(err.code, ErrorCode::InvalidAddress);
+            assert!(err.message.contains("doesn't match any configured network"));
+        }
+
+        // A custom network's prefix is accepted, but its addresses aren't registered
+        let mut custom = registry.clone();
+        let mut config = custom.networks[0].clone();
+        config.network = ArchwayNetwork::Local;
+        config.bech32_prefix = "osmo".to_string();
+        custom.networks.push(config);
+        let matched = resolve_address(&osmo, ContractRole::Token, None, None, &custom).unwrap();
+        assert_eq!(matched.role, None);
+        assert!(matched.warning.is_some());
+    }
+
+    #[test]
+    fn resolving_matches_the_registry() {
+        let registry = Registry::default();
+        let matched = resolve_address(
+            CONTRACT_MAINNET,
+            ContractRole::Marketplace,
+            None,
+            None,
+            &registry,
+        )
+        .unwrap();
+        assert_eq!(matched.network, Some(ArchwayNetwork::Mainnet));
+        assert_eq!(matched.warning, None);
+
+        let err = resolve_address(CONTRACT_MAINNET, ContractRole::Token, None, None, &registry)
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::AddressMismatch);
+    }
+}
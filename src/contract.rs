@@ -11,8 +11,8 @@ pub struct AmburContract {
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct CollectionContract {
-    pub token: [AmburContract; 2],
-    pub minter: [AmburContract; 2],
+    pub token: Vec<AmburContract>,
+    pub minter: Vec<AmburContract>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -65,6 +65,13 @@ pub static MINTER_GHOULS_MAINNET: &str =
 pub static MINTER_GHOULS_CONSTANTINE: &str =
     "archway17grtrpcg3pgm6mvcg4g603fq8065mk3gdwng8a9kum4p8hx8jptsa9zrhn";
 
+// Payment tokens (mainnet)
+pub static USDC_DENOM_MAINNET: &str =
+    "ibc/43897B9739BD63E3A08A88191999C632E052724AB96BD4C74AE31375C991F48D";
+pub static WUSDC_MAINNET: &str =
+    "archway1gaf9nw7n8v5lpjz9caxjpps006kxfcrzcuc8y5qp4clslhven2ns2g0ule";
+pub static USDC_DECIMALS: u32 = 6;
+
 // Collections (descriptions)
 pub static ARCHIES_DESCR: &str = "Archies is a collective that brings together Archway pioneers: innovators, creators, and those daring to push the boundaries of blockchain in unconventional ways.";
 pub static FORESIGHT_DESCR: &str = "The Foresight Ticket grants you a whitelist mint spot in one of the future Ambur collections. So keep an eye out, or three, for upcoming collections. Choose wisely.";
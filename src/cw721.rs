@@ -0,0 +1,192 @@
+use cosmwasm_std::Binary;
+use cw_utils::Expiration;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::AmburError;
+use crate::query::normalize;
+
+// cw721 messages, as specified by the cw721 package the Ambur tokens implement.
+// They're used for a collection's token unless its own schema is loaded, which
+// also covers its extension messages
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw721ExecuteMsg {
+    TransferNft {
+        recipient: String,
+        token_id: String,
+    },
+    SendNft {
+        contract: String,
+        token_id: String,
+        msg: Binary,
+    },
+    Approve {
+        spender: String,
+        token_id: String,
+        expires: Option<Expiration>,
+    },
+    Revoke {
+        spender: String,
+        token_id: String,
+    },
+    ApproveAll {
+        operator: String,
+        expires: Option<Expiration>,
+    },
+    RevokeAll {
+        operator: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw721QueryMsg {
+    OwnerOf {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    Approval {
+        token_id: String,
+        spender: String,
+        include_expired: Option<bool>,
+    },
+    Approvals {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    Operator {
+        owner: String,
+        operator: String,
+        include_expired: Option<bool>,
+    },
+    AllOperators {
+        owner: String,
+        include_expired: Option<bool>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    NumTokens {},
+    ContractInfo {},
+    NftInfo {
+        token_id: String,
+    },
+    AllNftInfo {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    Tokens {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    AllTokens {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    Minter {},
+}
+
+// cw721 query responses
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Approval {
+    pub spender: String,
+    pub expires: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OwnerOfResponse {
+    pub owner: String,
+    pub approvals: Vec<Approval>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ApprovalResponse {
+    pub approval: Approval,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ApprovalsResponse {
+    pub approvals: Vec<Approval>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperatorResponse {
+    pub approval: Approval,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperatorsResponse {
+    pub operators: Vec<Approval>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NumTokensResponse {
+    pub count: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ContractInfoResponse {
+    pub name: String,
+    pub symbol: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NftInfoResponse<T> {
+    pub token_uri: Option<String>,
+    pub extension: T,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllNftInfoResponse<T> {
+    pub access: OwnerOfResponse,
+    pub info: NftInfoResponse<T>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokensResponse {
+    pub tokens: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MinterResponse {
+    pub minter: Option<String>,
+}
+
+pub fn token_return_type(variant: &str) -> Option<String> {
+    let response_type = match variant {
+        "owner_of" => "OwnerOfResponse",
+        "approval" => "ApprovalResponse",
+        "approvals" => "ApprovalsResponse",
+        "operator" => "OperatorResponse",
+        "all_operators" => "OperatorsResponse",
+        "num_tokens" => "NumTokensResponse",
+        "contract_info" => "ContractInfoResponse",
+        "nft_info" => "NftInfoResponse",
+        "all_nft_info" => "AllNftInfoResponse",
+        "tokens" | "all_tokens" => "TokensResponse",
+        "minter" => "MinterResponse",
+        _ => return None,
+    };
+    Some(response_type.to_string())
+}
+
+// The metadata extension of NFT info responses differs per collection, and is
+// passed through as it is
+pub fn decode_token_response(response_type: &str, data: Value) -> Result<Value, AmburError> {
+    match response_type {
+        "OwnerOfResponse" => normalize::<OwnerOfResponse>(data),
+        "ApprovalResponse" => normalize::<ApprovalResponse>(data),
+        "ApprovalsResponse" => normalize::<ApprovalsResponse>(data),
+        "OperatorResponse" => normalize::<OperatorResponse>(data),
+        "OperatorsResponse" => normalize::<OperatorsResponse>(data),
+        "NumTokensResponse" => normalize::<NumTokensResponse>(data),
+        "ContractInfoResponse" => normalize::<ContractInfoResponse>(data),
+        "TokensResponse" => normalize::<TokensResponse>(data),
+        "MinterResponse" => normalize::<MinterResponse>(data),
+        "NftInfoResponse" => normalize::<NftInfoResponse<Value>>(data),
+        "AllNftInfoResponse" => normalize::<AllNftInfoResponse<Value>>(data),
+        _ => Ok(data),
+    }
+}
@@ -0,0 +1,202 @@
+use rmcp::model::{CallToolResult, Content};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::fmt;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    InvalidJson,
+    InvalidMessage,
+    RuleViolation,
+    SwapIdInUse,
+    InvalidAmount,
+    InvalidExpiration,
+    InvalidAddress,
+    AddressMismatch,
+    InvalidRegistry,
+    InvalidSchema,
+    InvalidNetwork,
+    QueriesDisabled,
+    QueryFailed,
+    SigningDisabled,
+    InvalidKey,
+    SigningFailed,
+    UnknownCollection,
+    CollectionSchemaNotLoaded,
+    UnknownContract,
+    UnknownPaymentToken,
+    UnknownResource,
+    UnknownPrompt,
+    MissingPromptArgument,
+    SerializationFailed,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AmburError {
+    pub code: ErrorCode,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+}
+
+impl AmburError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            expected_type: None,
+            path: None,
+            line: None,
+            column: None,
+        }
+    }
+
+    pub fn unknown_collection(nft: &str) -> Self {
+        Self::new(
+            ErrorCode::UnknownCollection,
+            format!("Unrecognized NFT collection name {nft}"),
+        )
+    }
+
+    pub fn invalid_amount(amount: &str) -> Self {
+        Self::new(
+            ErrorCode::InvalidAmount,
+            format!("Invalid payment amount {amount}, expected an unsigned integer string"),
+        )
+    }
+
+    pub fn serialization<T>(message: impl Into<String>) -> Self {
+        Self {
+            expected_type: Some(std::any::type_name::<T>().to_string()),
+            ..Self::new(ErrorCode::SerializationFailed, message)
+        }
+    }
+}
+
+impl fmt::Display for AmburError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}: {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for AmburError {}
+
+impl From<AmburError> for CallToolResult {
+    fn from(err: AmburError) -> Self {
+        let serialized = serde_json::to_string(&err).unwrap_or_else(|_| err.to_string());
+        CallToolResult::error(vec![Content::text(serialized)])
+    }
+}
+
+// Resource reads and prompts fail with a JSON-RPC error, carrying the AmburError as its data
+impl From<AmburError> for rmcp::Error {
+    fn from(err: AmburError) -> Self {
+        let data = serde_json::to_value(&err).ok();
+        match err.code {
+            ErrorCode::UnknownResource
+            | ErrorCode::UnknownCollection
+            | ErrorCode::CollectionSchemaNotLoaded
+            | ErrorCode::UnknownContract
+            | ErrorCode::InvalidNetwork => rmcp::Error::resource_not_found(err.message, data),
+            _ => rmcp::Error::invalid_params(err.message, data),
+        }
+    }
+}
+
+// Deserialize a tool input into `T`, recording where it diverged from the expected type
+pub fn from_json_str<T: DeserializeOwned>(input: &str) -> Result<T, AmburError> {
+    let de = &mut serde_json::Deserializer::from_str(input);
+    let deserialized: T = serde_path_to_error::deserialize(&mut *de).map_err(|err| {
+        let path = err.path().to_string();
+        json_error::<T>(err.into_inner(), Some(path).filter(|path| path != "."))
+    })?;
+    de.end().map_err(|err| json_error::<T>(err, None))?;
+    Ok(deserialized)
+}
+
+// Same as `from_json_str`, for inputs that have already been parsed as JSON
+pub fn from_json_value<T: DeserializeOwned>(input: serde_json::Value) -> Result<T, AmburError> {
+    serde_path_to_error::deserialize(input).map_err(|err| {
+        let path = err.path().to_string();
+        json_error::<T>(err.into_inner(), Some(path).filter(|path| path != "."))
+    })
+}
+
+fn json_error<T>(err: serde_json::Error, path: Option<String>) -> AmburError {
+    let code = if err.is_data() {
+        ErrorCode::InvalidMessage
+    } else {
+        ErrorCode::InvalidJson
+    };
+    AmburError {
+        code,
+        message: err.to_string(),
+        expected_type: Some(std::any::type_name::<T>().to_string()),
+        path,
+        line: Some(err.line()).filter(|line| *line > 0),
+        column: Some(err.column()).filter(|column| *column > 0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use philabs_cw721_marketplace::msg::ExecuteMsg;
+
+    #[test]
+    fn from_json_str_locates_type_errors() {
+        let execute_msg = r#"{
+  "create": {
+    "id": "3b1f6f8e",
+    "cw721": "archway1cw721",
+    "token_id": "427",
+    "payment_token": { "native": { "denom": "uusdc" } },
+    "price": 8880000,
+    "swap_type": "Sale",
+    "expires": { "never": {} }
+  }
+}"#;
+        let err = from_json_str::<ExecuteMsg>(execute_msg).unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidMessage);
+        assert_eq!(err.path.as_deref(), Some("create.price"));
+        assert_eq!((err.line, err.column), (Some(7), Some(20)));
+        assert_eq!(
+            err.expected_type.as_deref(),
+            Some(std::any::type_name::<ExecuteMsg>())
+        );
+        assert!(err.expected_type.unwrap().ends_with("::ExecuteMsg"));
+        assert!(
+            err.message.contains("expected string-encoded integer"),
+            "{}",
+            err.message
+        );
+
+        // Unknown variants are reported at the message's root
+        let err = from_json_str::<ExecuteMsg>("{\"buy\": {\"id\": \"3b1f6f8e\"}}").unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidMessage);
+        assert_eq!(err.path, None);
+        assert_eq!((err.line, err.column), (Some(1), Some(6)));
+        assert!(
+            err.message.contains("unknown variant `buy`"),
+            "{}",
+            err.message
+        );
+
+        // Malformed JSON, and trailing characters after a valid message
+        let err = from_json_str::<ExecuteMsg>("{\"finish\": {\"id\": \"3b1f6f8e\"}\n").unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidJson);
+        // serde_json reports the end of input at column 0, which is left out
+        assert_eq!((err.line, err.column), (Some(2), None));
+        let err =
+            from_json_str::<ExecuteMsg>("{\"finish\": {\"id\": \"3b1f6f8e\"}} }").unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidJson);
+        assert_eq!((err.line, err.column), (Some(1), Some(32)));
+    }
+}
@@ -1,8 +1,131 @@
+use cosmwasm_std::{Binary, CosmosMsg, to_json_binary};
+use cw20::Cw20ExecuteMsg;
+use philabs_cw721_marketplace::msg::ExecuteMsg;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::address::{ContractMatch, ContractRole};
+use crate::error::{AmburError, ErrorCode, from_json_str};
+use crate::family::{ContractCollectionName, contract_family};
+use crate::schema::ContractSchema;
+use crate::tx::EncodedTxBody;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ValidatedExecute {
     pub execute_msg: String,
     pub cosmos_msg: String,
+    pub contract: ContractMatch,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_body: Option<EncodedTxBody>,
+    // Rules that couldn't be checked, e.g. without live queries
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BundleEntry {
+    #[schemars(
+        description = "kind of contract the message is sent to (\"marketplace\", \"token\", \"minter\" or \"cw20\")"
+    )]
+    pub contract_kind: ContractRole,
+    #[schemars(with = "Option<ContractCollectionName>")]
+    pub nft: Option<String>,
+    #[schemars(description = "contract address the message is sent to")]
+    pub contract_addr: String,
+    #[schemars(
+        description = "JSON stringified ExecuteMsg variant of the contract, as for the build_*_execute_msg tools (Cw20ExecuteMsg for cw20 tokens)"
+    )]
+    pub execute_msg: String,
+    #[schemars(
+        description = "Optionally include native payment funds to be sent with this message, in base units or as a human amount (e.g. \"8.88 USDC\")"
+    )]
+    pub payment: Option<String>,
+    #[schemars(
+        description = "Optionally include native payment denom (or registered symbol) for the funds, not needed when the payment includes its symbol"
+    )]
+    pub payment_denom: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ValidatedBundle {
+    // JSON stringified CosmosMsg values, in transaction order
+    pub cosmos_msgs: Vec<String>,
+    // Registry match of each message's contract, in the same order
+    pub contracts: Vec<ContractMatch>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_body: Option<EncodedTxBody>,
+    // Rules that couldn't be checked, prefixed with their message's position
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+impl ValidatedBundle {
+    // The bundle's messages as CosmosMsg values, for Rust callers
+    pub fn to_cosmos_msgs(&self) -> Result<Vec<CosmosMsg>, AmburError> {
+        self.cosmos_msgs
+            .iter()
+            .map(|cosmos_msg| from_json_str::<CosmosMsg>(cosmos_msg))
+            .collect()
+    }
+}
+
+pub fn marketplace_execute_binary(execute_msg: &str) -> Result<Binary, AmburError> {
+    to_json_binary(&from_json_str::<ExecuteMsg>(execute_msg)?)
+        .map_err(|err| AmburError::serialization::<Binary>(err.to_string()))
+}
+
+// Deserialize `execute_msg` as the ExecuteMsg of the contract kind (and collection)
+pub fn execute_binary(
+    schemas: &[ContractSchema],
+    role: ContractRole,
+    nft: Option<&str>,
+    execute_msg: &str,
+) -> Result<Binary, AmburError> {
+    let collection = || {
+        nft.ok_or_else(|| {
+            AmburError::new(
+                ErrorCode::UnknownCollection,
+                format!("An NFT collection name is required for {role:?} messages"),
+            )
+        })
+    };
+    match role {
+        ContractRole::Marketplace => marketplace_execute_binary(execute_msg),
+        ContractRole::Token | ContractRole::Minter => {
+            contract_family(schemas, collection()?, role)?.execute_binary(execute_msg)
+        }
+        ContractRole::Cw20 => to_json_binary(&from_json_str::<Cw20ExecuteMsg>(execute_msg)?)
+            .map_err(|err| AmburError::serialization::<Binary>(err.to_string())),
+    }
+}
+
+// Rewrite a field of an externally tagged ExecuteMsg variant (e.g. a human
+// `price`), reporting errors against the field's path. Messages are returned
+// unchanged when `rewrite` returns None, or when they aren't valid JSON, which
+// is then reported by the typed deserializer
+pub fn rewrite_variant_field(
+    execute_msg: &str,
+    field: &str,
+    rewrite: impl FnOnce(&Map<String, Value>) -> Result<Option<Value>, AmburError>,
+) -> Result<String, AmburError> {
+    let Ok(mut value) = serde_json::from_str::<Value>(execute_msg) else {
+        return Ok(execute_msg.to_string());
+    };
+    let Some((variant, Value::Object(fields))) = value
+        .as_object_mut()
+        .filter(|msg| msg.len() == 1)
+        .and_then(|msg| msg.iter_mut().next())
+    else {
+        return Ok(execute_msg.to_string());
+    };
+    let rewritten = rewrite(fields).map_err(|err| AmburError {
+        path: Some(format!("{variant}.{field}")),
+        ..err
+    })?;
+    let Some(rewritten) = rewritten else {
+        return Ok(execute_msg.to_string());
+    };
+    fields.insert(field.to_string(), rewritten);
+    serde_json::to_string(&value).map_err(|err| AmburError::serialization::<Value>(err.to_string()))
 }
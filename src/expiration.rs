@@ -0,0 +1,424 @@
+use chrono::{DateTime, NaiveDate, SecondsFormat, TimeDelta, Utc};
+use cosmwasm_std::Timestamp;
+use cw_utils::Expiration;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::{AmburError, ErrorCode, from_json_str};
+use crate::execute::rewrite_variant_field;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BuiltExpiration {
+    pub expires: Expiration,
+    // JSON stringified `expires`, as accepted by the 'expires' parameters of the build tools
+    pub expires_json: String,
+    pub description: String,
+    // RFC 3339 time an `at_time` expiration is reached, or the estimated time for `at_height`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_height: Option<u64>,
+}
+
+// A parsed expiration; relative block counts need the chain's current height
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExpirationInput {
+    Fixed(Expiration),
+    InBlocks(u64),
+}
+
+impl ExpirationInput {
+    pub fn is_height_based(&self) -> bool {
+        matches!(
+            self,
+            ExpirationInput::InBlocks(_) | ExpirationInput::Fixed(Expiration::AtHeight(_))
+        )
+    }
+
+    pub fn resolve(self, current_height: Option<u64>) -> Result<Expiration, AmburError> {
+        match (self, current_height) {
+            (ExpirationInput::Fixed(Expiration::AtHeight(height)), Some(current))
+                if height <= current =>
+            {
+                Err(invalid_expiration(format!(
+                    "Block height {height} has already been reached (the current height is {current})"
+                )))
+            }
+            (ExpirationInput::Fixed(expires), _) => Ok(expires),
+            (ExpirationInput::InBlocks(blocks), Some(current)) => {
+                Ok(Expiration::AtHeight(current + blocks))
+            }
+            (ExpirationInput::InBlocks(_), None) => Err(invalid_expiration(
+                "Expirations relative to the current block height need live queries and a network, give an absolute height instead (e.g. \"height 1234567\")",
+            )),
+        }
+    }
+}
+
+fn invalid_expiration(message: impl Into<String>) -> AmburError {
+    AmburError::new(ErrorCode::InvalidExpiration, message)
+}
+
+pub fn to_timestamp(time: DateTime<Utc>) -> Timestamp {
+    Timestamp::from_seconds(time.timestamp().max(0) as u64)
+        .plus_nanos(time.timestamp_subsec_nanos() as u64)
+}
+
+pub fn to_datetime(time: Timestamp) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(time.seconds() as i64, time.subsec_nanos() as u32)
+}
+
+pub fn rfc3339(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+// Duration units, singular and plural, with their length in seconds (None for
+// block counts)
+static DURATION_UNITS: [(&[&str], Option<i64>); 6] = [
+    (&["s", "sec", "secs", "second", "seconds"], Some(1)),
+    (&["m", "min", "mins", "minute", "minutes"], Some(60)),
+    (&["h", "hr", "hrs", "hour", "hours"], Some(60 * 60)),
+    (&["d", "day", "days"], Some(24 * 60 * 60)),
+    (&["w", "wk", "wks", "week", "weeks"], Some(7 * 24 * 60 * 60)),
+    (&["block", "blocks"], None),
+];
+
+// Seconds in one of a duration's units, or None for block counts
+fn unit_seconds(unit: &str) -> Result<Option<i64>, AmburError> {
+    DURATION_UNITS
+        .iter()
+        .find(|(names, _)| names.contains(&unit))
+        .map(|(_, seconds)| *seconds)
+        .ok_or_else(|| {
+            invalid_expiration(format!(
+                "Unknown duration unit {unit}, expected seconds, minutes, hours, days, weeks or blocks"
+            ))
+        })
+}
+
+// "7 days", "12h" or "100 blocks"; a count without a unit is refused, since
+// it could be seconds, blocks or a timestamp
+fn parse_duration(input: &str, now: DateTime<Utc>) -> Result<ExpirationInput, AmburError> {
+    let split = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (count, unit) = input.split_at(split);
+    let count: u64 = count
+        .parse()
+        .map_err(|_| invalid_expiration(format!("Invalid duration {input}")))?;
+    if unit.trim().is_empty() {
+        return Err(invalid_expiration(format!(
+            "Duration {input} has no unit, expected e.g. \"{count} days\", \"{count} hours\" or \"{count} blocks\""
+        )));
+    }
+    match unit_seconds(unit.trim())? {
+        None => Ok(ExpirationInput::InBlocks(count)),
+        Some(seconds) => {
+            let time = i64::try_from(count)
+                .ok()
+                .and_then(|count| count.checked_mul(seconds))
+                .and_then(TimeDelta::try_seconds)
+                .and_then(|delta| now.checked_add_signed(delta))
+                .ok_or_else(|| invalid_expiration(format!("Duration {input} is too long")))?;
+            Ok(ExpirationInput::Fixed(Expiration::AtTime(to_timestamp(
+                time,
+            ))))
+        }
+    }
+}
+
+// Refuse times that have passed; `time` is None when it's too far in the future
+// to be represented
+fn check_future(
+    input: &str,
+    time: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Result<(), AmburError> {
+    match time {
+        Some(time) if time <= now => Err(invalid_expiration(format!(
+            "{input} is in the past, expirations must be in the future"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+// Parse a human expiration: "never", a duration from `now` ("in 7 days", "12
+// hours", "in 100 blocks"), an RFC 3339 time or a date (midnight UTC), a block
+// height ("height 1234567" or "block 1234567"), or a JSON Expiration
+pub fn parse_expiration_input(
+    input: &str,
+    now: DateTime<Utc>,
+) -> Result<ExpirationInput, AmburError> {
+    let trimmed = input.trim();
+    if trimmed.starts_with('{') {
+        let expires = from_json_str::<Expiration>(trimmed)?;
+        if let Expiration::AtTime(time) = expires {
+            check_future(input, to_datetime(time), now)?;
+        }
+        return Ok(ExpirationInput::Fixed(expires));
+    }
+    let lower = trimmed.to_lowercase();
+    let (trimmed, lower) = match lower.strip_prefix("at ") {
+        Some(rest) => (trimmed[3..].trim_start(), rest.trim_start()),
+        None => (trimmed, lower.as_str()),
+    };
+    if lower.is_empty() || lower == "never" {
+        return Ok(ExpirationInput::Fixed(Expiration::Never {}));
+    }
+    if let Some(duration) = lower.strip_prefix("in ") {
+        return parse_duration(duration.trim(), now);
+    }
+    for prefix in ["block height ", "height ", "block "] {
+        if let Some(height) = lower.strip_prefix(prefix) {
+            let height = height
+                .trim()
+                .parse()
+                .map_err(|_| invalid_expiration(format!("Invalid block height in {input}")))?;
+            return Ok(ExpirationInput::Fixed(Expiration::AtHeight(height)));
+        }
+    }
+    let time = DateTime::parse_from_rfc3339(trimmed)
+        .map(|time| time.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(trimmed, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+                .map(|time| time.and_utc())
+        });
+    if let Some(time) = time {
+        check_future(input, Some(time), now)?;
+        return Ok(ExpirationInput::Fixed(Expiration::AtTime(to_timestamp(
+            time,
+        ))));
+    }
+    // Durations without "in" (e.g. "12h"), which must still have a unit
+    if lower.starts_with(|c: char| c.is_ascii_digit()) {
+        return parse_duration(lower, now);
+    }
+    Err(invalid_expiration(format!(
+        "Invalid expiration {input}, expected e.g. \"in 7 days\", \"2026-12-31T00:00:00Z\", \"height 1234567\" or \"never\""
+    )))
+}
+
+// A human expiration that doesn't depend on the current block height
+pub fn parse_expiration_str(input: &str) -> Result<Expiration, AmburError> {
+    parse_expiration_input(input, Utc::now())?.resolve(None)
+}
+
+// Convert a human `expires` (e.g. "in 7 days") in a marketplace ExecuteMsg to
+// an Expiration
+pub fn normalize_expires(execute_msg: &str) -> Result<String, AmburError> {
+    rewrite_variant_field(execute_msg, "expires", |fields| {
+        let Some(Value::String(expires)) = fields.get("expires") else {
+            return Ok(None);
+        };
+        let expires = parse_expiration_str(expires)?;
+        serde_json::to_value(expires)
+            .map(Some)
+            .map_err(|err| AmburError::serialization::<Expiration>(err.to_string()))
+    })
+}
+
+// `expires_json` and a description of an expiration; height based expirations
+// are estimated from the current height and the network's block time
+pub fn built_expiration(
+    expires: Expiration,
+    now: DateTime<Utc>,
+    current_height: Option<u64>,
+    block_time_ms: Option<u64>,
+) -> Result<BuiltExpiration, AmburError> {
+    let expires_json = serde_json::to_string(&expires)
+        .map_err(|err| AmburError::serialization::<Expiration>(err.to_string()))?;
+    let (description, estimated_time) = match &expires {
+        Expiration::Never {} => ("Never expires".to_string(), None),
+        Expiration::AtTime(time) => {
+            let at = to_datetime(*time).map(rfc3339);
+            (
+                format!(
+                    "Expires at {} (unix time {})",
+                    at.clone().unwrap_or_default(),
+                    time.seconds()
+                ),
+                at,
+            )
+        }
+        Expiration::AtHeight(height) => match (current_height, block_time_ms) {
+            (Some(current), Some(block_time_ms)) => {
+                let blocks = height.saturating_sub(current);
+                let estimated = i64::try_from(blocks.saturating_mul(block_time_ms))
+                    .ok()
+                    .and_then(TimeDelta::try_milliseconds)
+                    .and_then(|delta| now.checked_add_signed(delta))
+                    .map(rfc3339);
+                (
+                    format!(
+                        "Expires at block height {height}, {blocks} blocks after the current height {current}; estimated around {} at ~{block_time_ms}ms per block",
+                        estimated.clone().unwrap_or_default()
+                    ),
+                    estimated,
+                )
+            }
+            (None, Some(block_time_ms)) => (
+                format!(
+                    "Expires at block height {height}; blocks take ~{block_time_ms}ms, enable live queries and give the network to estimate when it's reached"
+                ),
+                None,
+            ),
+            _ => (format!("Expires at block height {height}"), None),
+        },
+    };
+    Ok(BuiltExpiration {
+        expires,
+        expires_json,
+        description,
+        estimated_time,
+        current_height,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn at(time: &str) -> ExpirationInput {
+        let time = DateTime::parse_from_rfc3339(time)
+            .unwrap()
+            .with_timezone(&Utc);
+        ExpirationInput::Fixed(Expiration::AtTime(to_timestamp(time)))
+    }
+
+    fn parse(input: &str) -> Result<ExpirationInput, AmburError> {
+        parse_expiration_input(input, now())
+    }
+
+    #[test]
+    fn parses_durations_with_a_unit() {
+        assert_eq!(parse("in 7 days").unwrap(), at("2026-01-08T00:00:00Z"));
+        assert_eq!(parse("7 days").unwrap(), at("2026-01-08T00:00:00Z"));
+        assert_eq!(parse("In 12 Hours").unwrap(), at("2026-01-01T12:00:00Z"));
+        assert_eq!(parse("12h").unwrap(), at("2026-01-01T12:00:00Z"));
+        assert_eq!(parse("in 30 min").unwrap(), at("2026-01-01T00:30:00Z"));
+        assert_eq!(parse("in 45s").unwrap(), at("2026-01-01T00:00:45Z"));
+        assert_eq!(parse("in 2 weeks").unwrap(), at("2026-01-15T00:00:00Z"));
+        assert_eq!(
+            parse("in 100 blocks").unwrap(),
+            ExpirationInput::InBlocks(100)
+        );
+        assert!(parse(&format!("in {} weeks", u64::MAX)).is_err());
+        assert!(parse("in 7 fortnights").is_err());
+    }
+
+    #[test]
+    fn accepts_singular_and_plural_units_only() {
+        assert_eq!(parse("in 1 day").unwrap(), at("2026-01-02T00:00:00Z"));
+        assert_eq!(parse("in 2 hrs").unwrap(), at("2026-01-01T02:00:00Z"));
+        assert_eq!(parse("in 90 secs").unwrap(), at("2026-01-01T00:01:30Z"));
+        assert_eq!(parse("in 1 block").unwrap(), ExpirationInput::InBlocks(1));
+        // "ms" isn't minutes, and units aren't trimmed of extra s's
+        for input in [
+            "in 500 ms",
+            "in 7 dayss",
+            "in 12 hourss",
+            "in 100 blockss",
+            "in 2 ss",
+        ] {
+            let err = parse(input).unwrap_err();
+            assert_eq!(err.code, ErrorCode::InvalidExpiration);
+            assert!(err.message.contains("Unknown duration unit"), "{input}");
+        }
+    }
+
+    #[test]
+    fn refuses_durations_without_a_unit() {
+        for input in ["7", "in 7", "in 7 ", "1747415615"] {
+            let err = parse(input).unwrap_err();
+            assert_eq!(err.code, ErrorCode::InvalidExpiration);
+            assert!(err.message.contains("no unit"), "{input}");
+        }
+    }
+
+    #[test]
+    fn parses_times_heights_and_never() {
+        assert_eq!(
+            parse("2026-12-31T18:00:00Z").unwrap(),
+            at("2026-12-31T18:00:00Z")
+        );
+        assert_eq!(parse("2026-12-31").unwrap(), at("2026-12-31T00:00:00Z"));
+        assert_eq!(
+            parse("at 2026-12-31T18:00:00+02:00").unwrap(),
+            at("2026-12-31T16:00:00Z")
+        );
+        for input in ["height 1234567", "block 1234567", "block height 1234567"] {
+            assert_eq!(
+                parse(input).unwrap(),
+                ExpirationInput::Fixed(Expiration::AtHeight(1234567))
+            );
+        }
+        for input in ["never", "Never", ""] {
+            assert_eq!(
+                parse(input).unwrap(),
+                ExpirationInput::Fixed(Expiration::Never {})
+            );
+        }
+        assert!(parse("height soon").is_err());
+        assert!(parse("tomorrow").is_err());
+    }
+
+    #[test]
+    fn refuses_past_times() {
+        for input in ["2025-12-31T23:59:59Z", "2026-01-01", "2026-01-01T00:00:00Z"] {
+            let err = parse(input).unwrap_err();
+            assert_eq!(err.code, ErrorCode::InvalidExpiration, "{input}");
+        }
+    }
+
+    #[test]
+    fn checks_json_expirations() {
+        assert_eq!(
+            parse(r#"{"at_time":"1798761600000000000"}"#).unwrap(),
+            at("2027-01-01T00:00:00Z")
+        );
+        assert_eq!(
+            parse(r#"{"at_height":1234567}"#).unwrap(),
+            ExpirationInput::Fixed(Expiration::AtHeight(1234567))
+        );
+        assert_eq!(
+            parse(r#"{"never":{}}"#).unwrap(),
+            ExpirationInput::Fixed(Expiration::Never {})
+        );
+        let err = parse(r#"{"at_time":"1"}"#).unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidExpiration);
+        assert!(parse(r#"{"at_time":"soon"}"#).is_err());
+    }
+
+    #[test]
+    fn resolves_block_expirations_against_the_current_height() {
+        assert_eq!(
+            ExpirationInput::InBlocks(100).resolve(Some(1000)).unwrap(),
+            Expiration::AtHeight(1100)
+        );
+        assert!(ExpirationInput::InBlocks(100).resolve(None).is_err());
+        let passed = ExpirationInput::Fixed(Expiration::AtHeight(1000));
+        assert!(passed.clone().resolve(Some(1000)).is_err());
+        assert_eq!(passed.resolve(None).unwrap(), Expiration::AtHeight(1000));
+    }
+
+    #[test]
+    fn normalizes_human_expires_in_messages() {
+        let msg = r#"{"update":{"id":"1","expires":"never"}}"#;
+        let normalized: Value = serde_json::from_str(&normalize_expires(msg).unwrap()).unwrap();
+        assert_eq!(
+            normalized["update"]["expires"],
+            serde_json::json!({ "never": {} })
+        );
+        assert!(normalize_expires(r#"{"update":{"id":"1","expires":"7"}}"#).is_err());
+    }
+}
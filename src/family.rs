@@ -0,0 +1,345 @@
+use cosmwasm_std::{Binary, to_json_binary};
+use schemars::{JsonSchema, r#gen::SchemaGenerator, schema::Schema, schema_for};
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+use std::marker::PhantomData;
+
+use crate::address::ContractRole;
+use crate::cw721::{Cw721ExecuteMsg, Cw721QueryMsg};
+use crate::error::{AmburError, ErrorCode, from_json_str};
+use crate::schema::{ContractSchema, MsgKind, find_schema};
+
+// The QueryMsg and ExecuteMsg of one kind of contract (a collection's cw721
+// token or its minter), behind an interface the tools can use without knowing
+// where the messages are defined
+pub trait ContractFamily: Send + Sync {
+    fn query_schema(&self) -> Result<Value, AmburError>;
+
+    fn execute_schema(&self) -> Result<Value, AmburError>;
+
+    // Validate `query_msg` against the QueryMsg, and serialize it for a WasmQuery
+    fn query_binary(&self, query_msg: &str) -> Result<Binary, AmburError>;
+
+    // Validate `execute_msg` against the ExecuteMsg, and serialize it for a WasmMsg
+    fn execute_binary(&self, execute_msg: &str) -> Result<Binary, AmburError>;
+}
+
+fn typed_schema<T: JsonSchema>() -> Result<Value, AmburError> {
+    serde_json::to_value(schema_for!(T))
+        .map_err(|err| AmburError::serialization::<Value>(err.to_string()))
+}
+
+fn typed_binary<T: DeserializeOwned + Serialize>(msg: &str) -> Result<Binary, AmburError> {
+    to_json_binary(&from_json_str::<T>(msg)?)
+        .map_err(|err| AmburError::serialization::<Binary>(err.to_string()))
+}
+
+// Messages defined by Rust types
+pub struct TypedFamily<Q, E> {
+    query: PhantomData<fn() -> Q>,
+    execute: PhantomData<fn() -> E>,
+}
+
+impl<Q, E> TypedFamily<Q, E> {
+    const fn new() -> Self {
+        Self {
+            query: PhantomData,
+            execute: PhantomData,
+        }
+    }
+}
+
+impl<Q, E> ContractFamily for TypedFamily<Q, E>
+where
+    Q: JsonSchema + DeserializeOwned + Serialize,
+    E: JsonSchema + DeserializeOwned + Serialize,
+{
+    fn query_schema(&self) -> Result<Value, AmburError> {
+        typed_schema::<Q>()
+    }
+
+    fn execute_schema(&self) -> Result<Value, AmburError> {
+        typed_schema::<E>()
+    }
+
+    fn query_binary(&self, query_msg: &str) -> Result<Binary, AmburError> {
+        typed_binary::<Q>(query_msg)
+    }
+
+    fn execute_binary(&self, execute_msg: &str) -> Result<Binary, AmburError> {
+        typed_binary::<E>(execute_msg)
+    }
+}
+
+// Messages defined by a cosmwasm-schema schema loaded with --schema-dir
+impl ContractFamily for ContractSchema {
+    fn query_schema(&self) -> Result<Value, AmburError> {
+        self.required_msg_schema(MsgKind::Query).cloned()
+    }
+
+    fn execute_schema(&self) -> Result<Value, AmburError> {
+        self.required_msg_schema(MsgKind::Execute).cloned()
+    }
+
+    fn query_binary(&self, query_msg: &str) -> Result<Binary, AmburError> {
+        self.msg_binary(MsgKind::Query, query_msg)
+    }
+
+    fn execute_binary(&self, execute_msg: &str) -> Result<Binary, AmburError> {
+        self.msg_binary(MsgKind::Execute, execute_msg)
+    }
+}
+
+// Tokens whose own schema isn't loaded still take the standard cw721 messages
+static CW721_TOKEN: TypedFamily<Cw721QueryMsg, Cw721ExecuteMsg> = TypedFamily::new();
+
+pub struct CollectionFamily {
+    // Lowercase names the collection is known by in tool calls
+    pub names: &'static [&'static str],
+    // URL-safe name of the collection in resource URIs, one of its names
+    pub slug: &'static str,
+    // Contract names (those of their crates) in the cosmwasm-schema API files
+    // of the collection's token and minter contracts
+    pub token_schema: &'static str,
+    pub minter_schema: &'static str,
+}
+
+// The collections whose messages can be built. A new collection is added here,
+// with the contract names of its schemas (and its contract addresses in the
+// registry)
+pub static COLLECTION_FAMILIES: &[CollectionFamily] = &[
+    CollectionFamily {
+        names: &["archies"],
+        slug: "archies",
+        token_schema: "archies-token",
+        minter_schema: "whitelist-minter",
+    },
+    CollectionFamily {
+        names: &["derpies"],
+        slug: "derpies",
+        token_schema: "derpies-token",
+        minter_schema: "derpies-minter",
+    },
+    CollectionFamily {
+        names: &["ghouls"],
+        slug: "ghouls",
+        token_schema: "ghouls-token",
+        minter_schema: "ghouls-minter",
+    },
+    CollectionFamily {
+        names: &["the foresight ticket", "foresight ticket", "foresight"],
+        slug: "foresight",
+        token_schema: "ambur-wl-token",
+        minter_schema: "ambur-wl-minter",
+    },
+];
+
+// Collection names are matched exactly (ignoring case) against each family's
+// aliases; this is the only place collection names are matched
+pub fn collection_family(nft: &str) -> Result<&'static CollectionFamily, AmburError> {
+    let nft_lower = nft.trim().to_lowercase();
+    COLLECTION_FAMILIES
+        .iter()
+        .find(|family| family.names.contains(&nft_lower.as_str()))
+        .ok_or_else(|| AmburError::unknown_collection(nft))
+}
+
+// Each collection's name, quoted for the descriptions of collection parameters,
+// e.g. "archies", "derpies"
+pub fn collection_examples() -> String {
+    COLLECTION_FAMILIES
+        .iter()
+        .filter_map(|family| family.names.first())
+        .map(|name| format!("\"{name}\""))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// Schemas of collection name parameters (`#[schemars(with = "CollectionName")]`,
+// or `Option<ContractCollectionName>`), whose descriptions list the collections'
+// names since schemars descriptions must be literals
+pub struct CollectionName;
+
+pub struct ContractCollectionName;
+
+impl JsonSchema for CollectionName {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        "CollectionName".to_string()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        described_string(
+            generator,
+            format!(
+                "name of the NFT collection (e.g. {})",
+                collection_examples()
+            ),
+        )
+    }
+}
+
+impl JsonSchema for ContractCollectionName {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        "ContractCollectionName".to_string()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        described_string(
+            generator,
+            format!(
+                "Optionally include the name of the NFT collection (e.g. {}), required for token and minter contracts",
+                collection_examples()
+            ),
+        )
+    }
+}
+
+fn described_string(generator: &mut SchemaGenerator, description: String) -> Schema {
+    let mut schema = generator.subschema_for::<String>().into_object();
+    schema.metadata().description = Some(description);
+    Schema::Object(schema)
+}
+
+// Whether two names refer to the same collection: aliases of the same family,
+// or the same name for collections without a family
+pub fn same_collection(name: &str, other: &str) -> bool {
+    match (collection_family(name), collection_family(other)) {
+        (Ok(family), Ok(other_family)) => std::ptr::eq(family, other_family),
+        (Err(_), Err(_)) => name.trim().eq_ignore_ascii_case(other.trim()),
+        _ => false,
+    }
+}
+
+// The token or minter family of a collection: the contract's loaded schema,
+// or for tokens without one, the standard cw721 messages
+pub fn contract_family<'a>(
+    schemas: &'a [ContractSchema],
+    nft: &str,
+    role: ContractRole,
+) -> Result<&'a dyn ContractFamily, AmburError> {
+    let family = collection_family(nft)?;
+    let contract = match role {
+        ContractRole::Token => family.token_schema,
+        ContractRole::Minter => family.minter_schema,
+        ContractRole::Marketplace | ContractRole::Cw20 => {
+            return Err(AmburError::new(
+                ErrorCode::InvalidMessage,
+                format!("{role:?} contracts aren't part of an NFT collection"),
+            ));
+        }
+    };
+    match (find_schema(schemas, contract), role) {
+        (Ok(schema), _) => Ok(schema),
+        (Err(_), ContractRole::Token) => Ok(&CW721_TOKEN),
+        (Err(_), _) => Err(AmburError::new(
+            ErrorCode::CollectionSchemaNotLoaded,
+            format!(
+                "The schema of the {nft} {role:?} contract isn't loaded, restart the server with --schema-dir pointing at a directory with its cosmwasm-schema output (contract name {contract})"
+            ),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn collection_names_match_aliases_ignoring_case_and_whitespace() {
+        for name in ["archies", "Archies", "  ARCHIES\n", "\tarChies "] {
+            assert_eq!(collection_family(name).unwrap().slug, "archies", "{name:?}");
+        }
+        for name in ["The Foresight Ticket", "foresight ticket", " Foresight "] {
+            assert_eq!(
+                collection_family(name).unwrap().slug,
+                "foresight",
+                "{name:?}"
+            );
+        }
+        // Aliases are matched exactly, not by prefix or with inner whitespace
+        for name in [
+            "archie",
+            "archies token",
+            "the  foresight ticket",
+            "foresight-ticket",
+            "",
+        ] {
+            let err = collection_family(name).err().unwrap();
+            assert_eq!(err.code, ErrorCode::UnknownCollection, "{name:?}");
+        }
+    }
+
+    #[test]
+    fn same_collection_matches_aliases_of_one_family() {
+        assert!(same_collection("The Foresight Ticket", "foresight"));
+        assert!(same_collection("foresight ticket", " FORESIGHT "));
+        assert!(same_collection("Archies", "archies"));
+        assert!(!same_collection("foresight", "archies"));
+        assert!(!same_collection("archies", "Punks"));
+        // Collections without a family are compared by name
+        assert!(same_collection("Punks", " punks "));
+        assert!(!same_collection("Punks", "Apes"));
+    }
+
+    #[test]
+    fn contract_families_need_a_loaded_minter_schema() {
+        let err = contract_family(&[], "ghouls", ContractRole::Minter)
+            .err()
+            .unwrap();
+        assert_eq!(err.code, ErrorCode::CollectionSchemaNotLoaded);
+        assert_eq!(
+            serde_json::to_value(err.code).unwrap(),
+            "collection_schema_not_loaded"
+        );
+        assert!(err.message.contains("ghouls-minter"), "{}", err.message);
+
+        // Tokens without a loaded schema fall back to the standard cw721 messages
+        let token = contract_family(&[], "ghouls", ContractRole::Token).unwrap();
+        assert_eq!(
+            token.execute_schema().unwrap(),
+            typed_schema::<Cw721ExecuteMsg>().unwrap()
+        );
+        assert!(
+            token
+                .query_binary(r#"{"owner_of":{"token_id":"1"}}"#)
+                .is_ok()
+        );
+        assert!(token.query_binary(r#"{"mint":{"token_id":"1"}}"#).is_err());
+
+        // Loaded schemas are used for both, looked up by the family's contract names
+        let schema = |contract_name: &str| -> ContractSchema {
+            serde_json::from_value(json!({
+                "contract_name": contract_name,
+                "query": schema_for!(Cw721QueryMsg),
+                "execute": { "type": "object", "required": ["mint"] },
+            }))
+            .unwrap()
+        };
+        let schemas = [schema("ghouls-token"), schema("ghouls-minter")];
+        for role in [ContractRole::Token, ContractRole::Minter] {
+            let family = contract_family(&schemas, "Ghouls", role).unwrap();
+            assert_eq!(
+                family.execute_schema().unwrap(),
+                json!({ "type": "object", "required": ["mint"] })
+            );
+        }
+
+        for role in [ContractRole::Marketplace, ContractRole::Cw20] {
+            let err = contract_family(&schemas, "ghouls", role).err().unwrap();
+            assert_eq!(err.code, ErrorCode::InvalidMessage);
+        }
+        let err = contract_family(&schemas, "punks", ContractRole::Token)
+            .err()
+            .unwrap();
+        assert_eq!(err.code, ErrorCode::UnknownCollection);
+    }
+}
@@ -2,7 +2,7 @@
 pub static SERVER_INFO_DESCR: &str = r#"
 This MCP server provides tools for aiding with queries and transactions to 
 the Ambur Marketplace contract on Archway Network. It does not broadcast these 
-queries or txs, nor does it sign the txs. 
+queries or txs, and it only signs txs when local signing has been enabled ('sign_tx'). 
 
 It allows users to perform the following actions: 
 - List the available Archway contract addresses, and associated network, where the 
@@ -13,8 +13,18 @@ them ('list_query_entry_points')
 ('build_query_msg')
 - List the available execute (tx) entry points, and any parameters required for 
 building them ('list_tx_entry_points')
+- Look up a single query or execute entry point, with a minimal example message 
+('describe_entry_point')
 - Build an execute message (tx message) that can be signed and broadcast by any 
 RPC enabled tool with wallet signing capabilities
+- Build queries and execute messages for other contracts whose cosmwasm-schema JSON 
+schemas were loaded at startup ('list_schema_contracts')
+
+The message schemas and the registry are also available as MCP resources, e.g. 
+ambur://schema/marketplace/execute, 
+ambur://schema/{collection}/{token|minter}/{query|execute} and ambur://registry/{network}. 
+Guided workflows, such as listing an NFT for sale or minting from a collection's minter, 
+are available as MCP prompts.
 "#;
 
 // Tool descriptions
@@ -25,6 +35,74 @@ where the Ambur marketplace contract has been deployed. This tool is helpful for
 discovering the mainnet and testnet contract addresses for the Ambur marketplace 
 smart contract."#;
 
+pub static LIST_NETWORKS_DESCR: &str = r#"
+Call this tool to get a list of the Archway networks this server knows about (e.g. Mainnet, 
+Constantine, Titus, or a custom / local network), and the metadata for each network: its 
+chain-id, bech32 address prefix, default fee denom, and RPC and LCD endpoints. This tool is 
+helpful for knowing which chain-id or endpoint a prepared query or transaction belongs to."#;
+
+pub static LIST_PAYMENT_TOKENS_DESCR: &str = r#"
+Call this tool to get a list of the payment tokens registered for each network: their 
+symbol (e.g. 'USDC' or 'wUSDC'), the token itself (a native 'denom', or the contract 
+'address' of a cw20 token) and its number of decimals. Amounts in messages and funds are 
+always in base units, e.g. 8.88 USDC is "8880000" since USDC has 6 decimals; see the 
+'parse_amount' and 'format_amount' tools to convert between the two. Only Mainnet's tokens 
+are built in, so the marketplace workflows fail on networks without a registered USDC and 
+wUSDC (e.g. the testnets, unless the registry file adds them)."#;
+
+pub static PARSE_AMOUNT_DESCR: &str = r#"
+Call this tool to convert a human amount of a registered payment token (e.g. "8.88 USDC") 
+into base units (e.g. "8880000"), using the token's decimals from the registry (see tool: 
+'list_payment_tokens'). Amounts without a symbol (e.g. "8.88") need the payment 'token' 
+(its symbol, denom or cw20 address); whole numbers without a symbol (e.g. "8") are refused 
+as ambiguous, so give them with their symbol (e.g. "8 USDC") or a decimal point (e.g. "8.0"). 
+Include the 'network' when the token is registered on more than one network.
+
+The result has the amount in base units ('amount'), the human readable amount 
+('formatted') and the matching 'payment_token' from the registry."#;
+
+pub static FORMAT_AMOUNT_DESCR: &str = r#"
+Call this tool to convert an amount in base units (e.g. "8880000", as found in a swap's 
+'price' or in query responses) into a human readable amount of the payment 'token' (e.g. 
+"8.88 USDC"). The token can be given as its symbol, denom or cw20 address (see tool: 
+'list_payment_tokens'). Include the 'network' when the token is registered on more than one 
+network."#;
+
+pub static GENERATE_SWAP_ID_DESCR: &str = r#"
+Call this tool to get a new swap id for a marketplace 'create' or 'create_collection_offer' 
+message, instead of making one up. Never reuse the ids of the documentation's examples, or 
+of existing swaps.
+
+Without parameters a random (v4) UUID is generated. When the 'seller' address and the 'nft' 
+(collection name or cw721 address) are given, a deterministic (v5) UUID is derived from them, 
+the 'token_id' (leave it out for collection offers) and a 'nonce' (defaults to "0"), so that 
+the same swap always gets the same id, e.g. when a listing is retried. Change the nonce to 
+create another swap for the same NFT.
+
+When the 'network' is given and live queries are enabled, the marketplace's 'details' query 
+is used to confirm that the id is unused, and the result's 'checked' is true. An id that's 
+already used is refused with a 'swap_id_in_use' error. Only the marketplace's own "not found" 
+error confirms an unused id; any other failure of the query (e.g. an unreachable node) is 
+reported as an error saying the id couldn't be checked."#;
+
+pub static BUILD_EXPIRATION_DESCR: &str = r#"
+Call this tool to convert a human expiration 'input' into the Expiration value expected by 
+the 'expires' field of marketplace messages and cw721 approvals. The input can be a 
+duration from now (e.g. "in 7 days", "in 12 hours", "in 30 minutes", or "in 1000 blocks"), 
+an RFC 3339 time (e.g. "2026-12-31T18:00:00Z") or a date (e.g. "2026-12-31", at midnight 
+UTC), a block height (e.g. "height 1234567"), "never", or a JSON Expiration. Durations 
+need a unit (a bare number such as "7" or "in 7" is refused), and times in the past are 
+refused, including a JSON 'at_time'.
+
+The result contains the Expiration ('expires', one of `{"at_time": ...}` in nanoseconds, 
+`{"at_height": ...}` or `{"never": {}}`), the same value as stringified JSON 
+('expires_json'), and a plain language 'description'. Include the 'network' for block 
+based expirations: when live queries are enabled, the chain's current height is queried 
+to resolve block counts (e.g. "in 1000 blocks") and to estimate when the height will be 
+reached ('estimated_time') using the network's average block time (see tool: 
+'list_networks'). Block counts can't be resolved without live queries, give an absolute 
+height instead."#;
+
 pub static LIST_NFT_COLLECTIONS_DESCR: &str = r#"
 Call this tool to get a list of NFT collections that can be traded in the Ambur marketplace 
 contract. The detailed response will provide the following information about each NFT 
@@ -53,7 +131,8 @@ marketplace smart contract.
 
 The response provided from this tool is a JSON schema for the QueryMsg enum of the 
 Ambur maketplace contract. It would be too verbose to provide it to your chat 
-partner, so summarizing it will be crucial. 
+partner, so summarizing it will be crucial. To look up a single entry point, see 
+tool: 'describe_entry_point'.
 
 Below is some documentation to help you parse and understand the fields and 
 structure of the generated QueryMsg schema, so that you can extrapolate the possible 
@@ -221,6 +300,18 @@ fees). `fee_percentage` is _not_ currently denominated in basis points.
 ```DOCUMENTATION_END
 "#;
 
+pub static DECODE_QUERY_RESPONSE_DESCR: &str = r#"
+Call this tool to decode the raw result of a query that was built with 'build_query_msg', 
+'build_token_query_msg' or 'build_minter_query_msg' and broadcast by another tool. The raw 
+response can be JSON, JSON wrapped in an LCD `data` field, or base64 encoded JSON bytes.
+
+The return type of the query is inferred from the original QueryMsg variant ('query_msg'): 
+e.g. `ListResponse` or `DetailsResponse` for marketplace queries, and `OwnerOfResponse` or 
+`NftInfoResponse` for cw721 token queries. The response is validated against that type, and 
+returned as normalized JSON with the name of the return type and any notes that help to 
+interpret it (e.g. the denomination of prices). If the return type can't be inferred, the 
+response is returned as is, with 'validated' set to false."#;
+
 // Prepare a query message for RPC broadcast
 pub static BUILD_QUERY_MSG_DESCR: &str = r#"
 Call this tool to build a prepared query message for a query to the Ambur NFT marketplace 
@@ -266,6 +357,22 @@ example, a `details` QueryMsg variant can be submitted to the tool like this:
 "{\"details\":{\"id\":\"cca4e046-97ba-45b2-841b-9adca039545e\"}}"
 "#;
 
+// Run a query against a live network (opt-in)
+pub static EXECUTE_QUERY_DESCR: &str = r#"
+Call this tool to run a query against the Ambur NFT marketplace contract and get its result. 
+The query is sent to the LCD endpoint of the network the contract address is registered on 
+(see tool: 'list_networks'). This tool is only available if the server was started with 
+live queries enabled; otherwise use 'build_query_msg' and broadcast the query with an RPC 
+connected query tool.
+
+The calling parameters are the same as for 'build_query_msg': the Ambur contract address 
+('contract_addr') and the QueryMsg variant ('query_msg') in string format. If the contract 
+address isn't one of the registered Ambur deployments, also provide the 'network' to query.
+
+When the return type of the query is known (e.g. `ListResponse` or `DetailsResponse`, see 
+tool: 'list_query_responses'), the response is validated against that type, and the type 
+name is included in the 'response_type' field of the result."#;
+
 // Execute
 pub static LIST_TX_ENTRY_POINTS_DESCR: &str = r#"
 Call this tool to get a list of possible transactions that can be made (e.g.  
@@ -276,7 +383,8 @@ Ambur NFT marketplace smart contract.
 
 The response provided from this tool is a JSON schema for the ExecuteMsg enum of the 
 Ambur maketplace contract. It would be too verbose to provide it to your chat 
-partner, so summarizing it will be crucial. 
+partner, so summarizing it will be crucial. To look up a single entry point, see 
+tool: 'describe_entry_point'.
 
 Below is some documentation to help you parse and understand the fields and 
 structure of the generated ExecuteMsg schema, so that you can extrapolate the 
@@ -376,6 +484,24 @@ marketplace contract, and also inform them of the calling parameters if they ask
 more information about a specific transaction."#;
 
 // Prepare a transaction message for signing and RPC broadcast
+pub static DESCRIBE_ENTRY_POINT_DESCR: &str = r#"
+Call this tool to look up a single entry point of a contract, instead of reading through 
+the whole QueryMsg or ExecuteMsg schema returned by the 'list_*_entry_points' tools. The 
+calling parameters are the 'contract_kind' (`marketplace`, `token`, `minter`, or `cw20` for 
+a cw20 payment token such as wUSDC), the NFT collection name ('nft', required for `token` 
+and `minter` contracts), the name of the entry point ('variant', e.g. 
+`create_collection_offer`), and optionally the 'msg_type' (`execute` or `query`). When the 
+'msg_type' is omitted, the ExecuteMsg is searched first and then the QueryMsg.
+
+The result holds the JSON schema of just that entry point ('schema'), with the definitions 
+it references (e.g. `Uint128` or `Expiration`) inlined in place, so it can be read without 
+the rest of the enum; only recursive types are kept as '$ref's, under 'definitions'. It 
+also holds a minimal message that passes the schema ('example'): required fields only, 
+zero for numbers, and empty strings for addresses and other strings. The example shows the 
+message's shape, its values still have to be filled in before building the message with 
+the matching 'build_*' tool. The 'example' is null when no valid message could be 
+generated from the schema alone."#;
+
 pub static BUILD_EXECUTE_MSG_DESCR: &str = r#"
 Call this tool to build a prepared execute message for a transaction to the Ambur 
 NFT marketplace contract. This tool won't sign the message, or broadcast it to the 
@@ -457,15 +583,121 @@ denom for USDC on Archway Network (which is currently the only allowed native pa
 token).
 ```DOCUMENTATION_END
 
+Amounts can be given as human amounts: a 'price' such as "8.88 USDC" (or "8.88", in the 
+message's 'payment_token') is converted to base units before the message is built, and so 
+is a 'payment' such as "8.88 USDC", whose denom is then taken from the registry (see tool: 
+'list_payment_tokens'). Whole numbers without a symbol or decimal point (e.g. "8880000", as 
+in the examples above) are already base units and are sent as they are.
+
+These rules are enforced before the message is built, and violations are refused with a 
+'rule_violation' error explaining the problem (and the 'path' of the offending field): 
+'Sale' swaps must be paid in native USDC, 'Offer' swaps and collection offers in the wUSDC 
+CW20 token, the 'cw721' must be the token contract of a registered NFT collection (see tool: 
+'list_nft_collections') on the same network, an 'at_time' expiration ('expires') must be in 
+the future, and any 'fee_percentage' must be a whole number from 0 to 30. When live queries 
+are enabled, an 'update' of an 'Offer' swap is also refused if it changes the price, since 
+only the expiration of an Offer can be updated; otherwise the result has a 'warnings' entry 
+saying the update couldn't be checked.
+
+The 'id' of a 'create' or 'create_collection_offer' message can be left out, and a new 
+random id is generated (see tool: 'generate_swap_id'). The ids in the examples above must 
+never be reused. When live queries are enabled, the id is also checked against the 
+marketplace's 'details' query, and ids that are already used are refused with a 
+'swap_id_in_use' error.
+
+Expirations can be given as human expirations too: an 'expires' string such as "in 7 days", 
+"2026-12-31T18:00:00Z" or "height 1234567" is converted to an Expiration before the message 
+is built (see tool: 'build_expiration').
+
 Submit all three calling parameters (`contract_addr`, `execute_msg` and `payment`) 
 to tool in string format. For example, use stringified JSON for the `execute_msg` and 
 `payment` calling parameters."#;
 
+pub static BUILD_TX_BODY_DESCR: &str = r#"
+Call this tool to combine one or more prepared execute messages into a single unsigned 
+transaction body, for signing services that expect protobuf encoded transactions rather 
+than CosmosMsg JSON. Each message is encoded as a `cosmwasm.wasm.v1.MsgExecuteContract` 
+(wrapped in a protobuf `Any`) sent by the 'sender' address, and the messages are wrapped, 
+in order, in a `cosmos.tx.v1beta1.TxBody`.
+
+The 'cosmos_msgs' calling parameter is a list of the 'cosmos_msg' strings returned by the 
+'build_execute_msg', 'build_token_execute_msg' and 'build_minter_execute_msg' tools. The 
+result contains the base64 encoded TxBody bytes ('tx_body_bytes'), and its JSON equivalent 
+('tx_body') which can be shown to your chat partner for review before signing.
+
+For a single message, the build_*_execute_msg tools return the same TxBody when they're 
+called with the optional 'sender' parameter."#;
+
+pub static BUILD_TX_BUNDLE_DESCR: &str = r#"
+Call this tool to build several execute messages, for any mix of the Ambur marketplace, 
+cw721 token and minter contracts, that must be signed and broadcast together as a single 
+atomic transaction. For example, listing an NFT for sale takes a cw721 `approve` of the 
+marketplace on the token contract, followed by a marketplace `create`. This tool does not 
+sign or broadcast the transaction.
+
+The 'messages' calling parameter is an ordered list of messages; they are executed in that 
+order, and if any of them fails the whole transaction fails. Each message has:
+* 'contract_kind': the kind of contract (`marketplace`, `token`, `minter`, or `cw20` for 
+a cw20 payment token such as wUSDC)
+* 'nft': the name of the NFT collection (required for `token` and `minter` messages)
+* 'contract_addr': the contract address the message is sent to
+* 'execute_msg': the JSON stringified ExecuteMsg variant, exactly as it would be given to 
+the 'build_execute_msg', 'build_token_execute_msg' or 'build_minter_execute_msg' tools (or a 
+cw20 `Cw20ExecuteMsg`, e.g. `increase_allowance`)
+* 'payment' and 'payment_denom': optional native funds sent with the message
+
+Each message is validated the same way as with the single message build tools, including 
+the marketplace rules, human prices and expirations, and generated swap ids of 
+'build_execute_msg' for marketplace messages, and errors say which message (counting from 
+0) is invalid. The result contains the ordered 'cosmos_msgs', which can be passed as-is to 
+the 'build_sign_doc' tool, the registry match of each message's contract ('contracts'), any 
+'warnings' about rules that couldn't be checked, and when the optional 'sender' is 
+provided, the unsigned TxBody holding all of the messages ('tx_body')."#;
+
+pub static BUILD_SIGN_DOC_DESCR: &str = r#"
+Call this tool to build a complete, unsigned sign doc for one or more prepared execute 
+messages, for custody and signing flows that sign sign docs rather than messages. This tool 
+won't sign the sign doc, or broadcast the transaction.
+
+The 'sign_mode' calling parameter selects the kind of sign doc:
+* `direct`: a protobuf `SignDoc` (SIGN_MODE_DIRECT), made of the encoded `TxBody` and 
+`AuthInfo` (signer info and fee), the chain-id and the account number
+* `amino_json`: a legacy amino JSON `StdSignDoc` (SIGN_MODE_LEGACY_AMINO_JSON), serialized 
+with sorted keys and no whitespace
+
+The chain-id is taken from the 'network' (see tool: 'list_networks'), and the fee is paid in 
+the network's fee denom unless 'fee_denom' is provided. The account number and sequence of 
+the sender can be found by querying the sender's account on the network.
+
+The result contains a JSON representation of the sign doc for review ('sign_doc'), the exact 
+base64 encoded bytes to be signed ('sign_bytes'), and their SHA-256 hash 
+('sign_bytes_sha256'). The output is deterministic: calling this tool again with the same 
+parameters will return the same bytes and hash. The 'body_bytes' and 'auth_info_bytes' 
+needed to assemble the signed transaction are also returned."#;
+
+pub static SIGN_TX_DESCR: &str = r#"
+Call this tool to sign one or more prepared execute messages with the server's local signing 
+key, returning a signed transaction that is ready to be broadcast. This tool won't broadcast 
+the transaction. Signing is only available when the server was built with the `signing` 
+feature and started with signing enabled; otherwise this tool returns an error, and the 
+'build_sign_doc' tool should be used to have the transaction signed elsewhere.
+
+The calling parameters are the same as for the 'build_sign_doc' tool. The 'sender' must be 
+the address of the server's signing key on the selected network, and the signer's public key 
+is filled in automatically when 'public_key' is omitted. Before calling this tool, show your 
+chat partner the messages being signed, and get their confirmation.
+
+The result contains the sign doc that was signed ('sign_doc'), the base64 encoded protobuf 
+`TxRaw` bytes ('tx_bytes') and the transaction hash ('tx_hash'). The 'tx_bytes' can be 
+broadcast by POSTing `{"tx_bytes": <tx_bytes>, "mode": "BROADCAST_MODE_SYNC"}` to the 
+`/cosmos/tx/v1beta1/txs` endpoint of the network's LCD."#;
+
 // NFT Query
 pub static LIST_TOKEN_QUERY_ENTRY_POINTS_DESCR: &str =
     "List all contract query entry points for a cw721 token that can be traded on Ambur";
 pub static BUILD_TOKEN_QUERY_MSG_DESCR: &str =
     "Build a contract query for a cw721 token that can be traded on Ambur";
+pub static EXECUTE_TOKEN_QUERY_DESCR: &str = "Run a contract query for a cw721 token that can be traded on Ambur against a live network (requires live queries to be enabled)";
 
 // NFT Execute
 pub static LIST_TOKEN_TX_ENTRY_POINTS_DESCR: &str = "List all execute entry points (txs) that can be made to a cw721 contract for an NFT collection that can be traded on Ambur";
@@ -476,8 +708,146 @@ pub static BUILD_TOKEN_EXECUTE_MSG_DESCR: &str =
 pub static LIST_MINTER_QUERY_ENTRY_POINTS_DESCR: &str = "List all contract query entry points for the minter contract of a cw721 token that can be traded on Ambur";
 pub static BUILD_MINTER_QUERY_MSG_DESCR: &str =
     "Build a contract query for the minter contract of a cw721 token that can be traded on Ambur";
+pub static EXECUTE_MINTER_QUERY_DESCR: &str = "Run a contract query for the minter contract of a cw721 token that can be traded on Ambur against a live network (requires live queries to be enabled)";
 
 // Minter Execute
 pub static LIST_MINTER_TX_ENTRY_POINTS_DESCR: &str = "List all execute entry points (txs) that can be made to the minter contract for an NFT collection that can be traded on Ambur";
 pub static BUILD_MINTER_EXECUTE_MSG_DESCR: &str =
     "Build an execute message (tx) for the minter contract of a token that can be traded on Ambur";
+
+// Loaded contract schemas
+pub static LIST_SCHEMA_CONTRACTS_DESCR: &str = r#"
+List the contracts whose cosmwasm-schema JSON schemas were loaded with the server's 
+--schema-dir option. Messages for any of these contracts can be built and validated with the 
+'list_schema_entry_points', 'build_schema_query_msg', 'execute_schema_query' and 
+'build_schema_execute_msg' tools, by passing the 'contract_name' as 'contract'. 
+
+Each contract lists the kinds of messages it has a schema for ('msgs') and the QueryMsg 
+variants whose responses are validated ('query_responses'). An empty list means no schemas 
+were loaded."#;
+
+pub static LIST_SCHEMA_ENTRY_POINTS_DESCR: &str = r#"
+List the entry points of a contract with a loaded schema, as the JSON schema of one kind of 
+its messages ('kind' is "instantiate", "execute", "query", "migrate" or "sudo"). Use the 
+schema to write the message passed to 'build_schema_query_msg', 'execute_schema_query' or 
+'build_schema_execute_msg'."#;
+
+pub static BUILD_SCHEMA_QUERY_MSG_DESCR: &str = r#"
+Build a contract query for a contract with a loaded schema ('contract', see 
+'list_schema_contracts'). The 'query_msg' is validated against the contract's QueryMsg 
+schema, and errors point at the invalid field of the message. The contract address doesn't 
+have to be registered, but it must belong to a configured network."#;
+
+pub static EXECUTE_SCHEMA_QUERY_DESCR: &str = r#"
+Run a contract query for a contract with a loaded schema ('contract', see 
+'list_schema_contracts') against a live network (requires live queries to be enabled). The 
+'query_msg' is validated against the contract's QueryMsg schema, and when the schema has a 
+response schema for the query's variant the response is validated against it and its type 
+is returned as 'response_type'. The 'network' is required when the contract address isn't 
+registered."#;
+
+pub static BUILD_SCHEMA_EXECUTE_MSG_DESCR: &str = r#"
+Build an execute message (tx) for a contract with a loaded schema ('contract', see 
+'list_schema_contracts'). The 'execute_msg' is validated against the contract's ExecuteMsg 
+schema, and errors point at the invalid field of the message. Native funds can be sent with 
+'payment' (and 'payment_denom' for base unit amounts), and the message is also encoded as 
+an unsigned TxBody when the 'sender' is provided."#;
+
+// Marketplace workflows
+pub static PREPARE_SALE_LISTING_DESCR: &str = r#"
+Call this tool to prepare everything needed to list an NFT for sale on the Ambur 
+marketplace, instead of building the messages by hand. This tool won't sign or broadcast 
+the transaction.
+
+The calling parameters are the NFT collection name ('nft'), the 'token_id' of the NFT, the 
+sale 'price' in USDC (e.g. "12.5 USDC"), an optional expiration ('expires', e.g. "in 7 
+days", defaults to never expiring) and the 'network'. The cw721 token and marketplace 
+contract addresses are taken from the registry for the network, the price is converted to 
+USDC's 6 decimal base units, and a new swap id is generated (and checked to be unused when 
+live queries are enabled).
+
+The result is a bundle of two messages that must be signed together as a single 
+transaction, in this order:
+1. a cw721 `approve` on the NFT's token contract, allowing the marketplace contract to 
+transfer the NFT when it sells
+2. a marketplace `create` of a 'Sale' swap, paid in Archway Network's native USDC
+
+The result also contains a plain language 'summary' of the listing, which should be shown 
+to your chat partner for confirmation before signing, the generated 'swap_id', and the 
+execute messages as JSON ('execute_msgs'). The bundle's 'cosmos_msgs' can be passed as-is to 
+the 'build_sign_doc' tool; when the seller's address is provided as 'sender', the unsigned 
+TxBody is returned as well."#;
+
+pub static PREPARE_PURCHASE_DESCR: &str = r#"
+Call this tool to prepare everything needed to buy an NFT listed for sale on the Ambur 
+marketplace, by finishing its 'Sale' swap. This tool won't sign or broadcast the transaction.
+
+The calling parameters are the 'swap_id' of the listing and the 'network'. The swap's 
+details (its price and payment token) are needed to pay the correct amount: provide the 
+response of the marketplace `details` query as 'details', or leave it out to have it queried 
+from the network (requires live queries to be enabled). Offers can't be purchased with this 
+tool, and neither can expired listings: listings expiring at a time are checked against the 
+current time, and listings expiring at a block height against the current height when live 
+queries are enabled.
+
+Depending on the swap's payment token, the result is a bundle of:
+* native payment tokens (e.g. USDC): a marketplace `finish` message, sent with the exact 
+price as funds
+* cw20 payment tokens (e.g. wUSDC): a cw20 `increase_allowance` allowing the marketplace 
+contract to spend the price, followed by the marketplace `finish` message; both must be 
+signed together as a single transaction, in this order
+
+The result also contains a plain language 'summary' of the purchase, which should be shown 
+to your chat partner for confirmation before signing, and the execute messages as JSON 
+('execute_msgs'). The bundle's 'cosmos_msgs' can be passed as-is to the 'build_sign_doc' 
+tool; when the buyer's address is provided as 'sender', the unsigned TxBody is returned as 
+well."#;
+
+pub static PREPARE_OFFER_DESCR: &str = r#"
+Call this tool to prepare everything needed to make an offer for a specific NFT on the Ambur 
+marketplace, instead of building the messages by hand. This tool won't sign or broadcast the 
+transaction.
+
+The calling parameters are the NFT collection name ('nft'), the 'token_id' of the NFT, the 
+offered 'price' in wUSDC (e.g. "12.5 wUSDC"), an optional expiration ('expires', e.g. "in 
+7 days", defaults to never expiring) and the 'network'. Offers can only be paid with a cw20 
+token, so native USDC prices are refused: the offer is always paid with the wUSDC cw20 
+token. A new swap id is generated (and checked to be unused when live queries are enabled).
+
+The result is a bundle of two messages that must be signed together as a single 
+transaction, in this order:
+1. a cw20 `increase_allowance` on the wUSDC contract, allowing the marketplace contract to 
+spend the offered price when the NFT's owner accepts the offer
+2. a marketplace `create` of an 'Offer' swap, paid in wUSDC
+
+The result also contains a plain language 'summary' of the offer, which should be shown to 
+your chat partner for confirmation before signing, the generated 'swap_id', and the execute 
+messages as JSON ('execute_msgs'). The bundle's 'cosmos_msgs' can be passed as-is to the 
+'build_sign_doc' tool; when the offerer's address is provided as 'sender', the unsigned 
+TxBody is returned as well."#;
+
+pub static PREPARE_COLLECTION_OFFER_DESCR: &str = r#"
+Call this tool to prepare everything needed to make a collection offer on the Ambur 
+marketplace, e.g. an offer to buy 1 or more NFTs from a collection regardless of their 
+'token_id's. This tool won't sign or broadcast the transaction.
+
+The calling parameters are the NFT collection name ('nft'), the 'quantity' of NFTs wanted, 
+the offered price in wUSDC given as either 'price_per_nft' or 'total_price' (exactly one of 
+them, e.g. "12.5 wUSDC"), an optional expiration ('expires', e.g. "in 7 days", defaults to 
+never expiring) and the 'network'. The `price` of a collection offer is the total for the 
+whole batch of NFTs, not the price per NFT: when 'price_per_nft' is given, it's multiplied 
+by the 'quantity' for you. Collection offers are always paid with the wUSDC cw20 token, 
+native USDC prices are refused. A new swap id is generated (and checked to be unused when 
+live queries are enabled).
+
+The result is a bundle of two messages that must be signed together as a single 
+transaction, in this order:
+1. a cw20 `increase_allowance` on the wUSDC contract, allowing the marketplace contract to 
+spend the total price
+2. a marketplace `create_collection_offer`, paid in wUSDC
+
+The result also contains a plain language 'summary' of the offer, which should be shown to 
+your chat partner for confirmation before signing, the generated 'swap_id', and the execute 
+messages as JSON ('execute_msgs'). The bundle's 'cosmos_msgs' can be passed as-is to the 
+'build_sign_doc' tool; when the offerer's address is provided as 'sender', the unsigned 
+TxBody is returned as well."#;
@@ -0,0 +1,233 @@
+use base64::{Engine, engine::general_purpose::URL_SAFE};
+use cosmwasm_std::Binary;
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::Value;
+use std::time::Duration;
+
+use crate::error::{AmburError, ErrorCode};
+
+#[derive(Deserialize)]
+struct SmartQueryResponse {
+    data: Value,
+}
+
+#[derive(Deserialize)]
+struct LatestBlockResponse {
+    block: Block,
+}
+
+#[derive(Deserialize)]
+struct Block {
+    header: BlockHeader,
+}
+
+// Heights are int64 strings in the LCD's JSON
+#[derive(Deserialize)]
+struct BlockHeader {
+    height: String,
+}
+
+// The gRPC gateway's body for queries the node answered with an error, e.g.
+// {"code":2,"message":"rpc error: ...: query wasm contract failed","details":[]}
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct LcdErrorBody {
+    pub code: i64,
+    pub message: String,
+}
+
+static QUERY_FAILED: &str = "Query failed with status ";
+
+// The error body of a failed query's response, None for any other failure (e.g.
+// a timeout, or a body that isn't the gateway's error)
+pub fn lcd_error_body(err: &AmburError) -> Option<LcdErrorBody> {
+    if err.code != ErrorCode::QueryFailed {
+        return None;
+    }
+    let (_, body) = err.message.strip_prefix(QUERY_FAILED)?.split_once(": ")?;
+    serde_json::from_str(body).ok()
+}
+
+#[derive(Clone, Debug)]
+pub struct LcdClient {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+// How long a query may take before it's given up on
+const LCD_TIMEOUT: Duration = Duration::from_secs(30);
+
+impl LcdClient {
+    pub fn new(base_url: &str) -> Result<Self, AmburError> {
+        Self::with_timeout(base_url, LCD_TIMEOUT)
+    }
+
+    pub fn with_timeout(base_url: &str, timeout: Duration) -> Result<Self, AmburError> {
+        let http = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|err| AmburError::new(ErrorCode::QueryFailed, err.to_string()))?;
+        Ok(Self {
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    // Runs a WasmQuery::Smart through the LCD's
+    // /cosmwasm/wasm/v1/contract/{addr}/smart/{b64} endpoint and returns its `data`
+    pub async fn smart_query(
+        &self,
+        contract_addr: &str,
+        msg: &Binary,
+    ) -> Result<Value, AmburError> {
+        let path = format!(
+            "/cosmwasm/wasm/v1/contract/{contract_addr}/smart/{}",
+            URL_SAFE.encode(msg.as_slice())
+        );
+        let parsed: SmartQueryResponse = self.get(&path).await?;
+        Ok(parsed.data)
+    }
+
+    // The height of the chain's latest block, from the LCD's
+    // /cosmos/base/tendermint/v1beta1/blocks/latest endpoint
+    pub async fn latest_block_height(&self) -> Result<u64, AmburError> {
+        let parsed: LatestBlockResponse = self
+            .get("/cosmos/base/tendermint/v1beta1/blocks/latest")
+            .await?;
+        parsed.block.header.height.parse().map_err(|_| {
+            AmburError::new(
+                ErrorCode::QueryFailed,
+                format!("Unexpected block height {}", parsed.block.header.height),
+            )
+        })
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, AmburError> {
+        let url = format!("{}{path}", self.base_url);
+        let failed = |message: String| AmburError::new(ErrorCode::QueryFailed, message);
+        let response =
+            self.http.get(&url).send().await.map_err(|err| {
+                failed(format!("Error sending query to {}: {err}", self.base_url))
+            })?;
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|err| failed(format!("Error reading query response: {err}")))?;
+        if !status.is_success() {
+            return Err(failed(format!("{QUERY_FAILED}{status}: {body}")));
+        }
+        serde_json::from_str(&body)
+            .map_err(|err| failed(format!("Unexpected query response {body}: {err}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    static CONTRACT: &str = "archway1contract";
+
+    fn smart_path(msg: &Binary) -> String {
+        format!(
+            "/cosmwasm/wasm/v1/contract/{CONTRACT}/smart/{}",
+            URL_SAFE.encode(msg.as_slice())
+        )
+    }
+
+    async fn mock(server: &MockServer, url_path: &str, response: ResponseTemplate) {
+        Mock::given(method("GET"))
+            .and(path(url_path))
+            .respond_with(response)
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn smart_query_returns_data() {
+        let server = MockServer::start().await;
+        let msg = Binary::from(br#"{"owner_of":{"token_id":"1"}}"#);
+        let response = ResponseTemplate::new(200)
+            .set_body_json(json!({ "data": { "owner": "archway1owner", "approvals": [] } }));
+        mock(&server, &smart_path(&msg), response).await;
+
+        let lcd = LcdClient::new(&format!("{}/", server.uri())).unwrap();
+        let data = lcd.smart_query(CONTRACT, &msg).await.unwrap();
+        assert_eq!(data, json!({ "owner": "archway1owner", "approvals": [] }));
+    }
+
+    #[tokio::test]
+    async fn smart_query_reports_http_errors() {
+        let server = MockServer::start().await;
+        let msg = Binary::from(br#"{"owner_of":{"token_id":"1"}}"#);
+        let response = ResponseTemplate::new(500)
+            .set_body_json(json!({ "code": 2, "message": "query wasm contract failed" }));
+        mock(&server, &smart_path(&msg), response).await;
+
+        let lcd = LcdClient::new(&server.uri()).unwrap();
+        let err = lcd.smart_query(CONTRACT, &msg).await.unwrap_err();
+        assert_eq!(err.code, ErrorCode::QueryFailed);
+        assert!(err.message.contains("500"));
+        assert!(err.message.contains("query wasm contract failed"));
+    }
+
+    #[tokio::test]
+    async fn smart_query_rejects_malformed_bodies() {
+        let server = MockServer::start().await;
+        let msg = Binary::from(br#"{"owner_of":{"token_id":"1"}}"#);
+        mock(
+            &server,
+            &smart_path(&msg),
+            ResponseTemplate::new(200).set_body_string("<html>"),
+        )
+        .await;
+        let lcd = LcdClient::new(&server.uri()).unwrap();
+        let err = lcd.smart_query(CONTRACT, &msg).await.unwrap_err();
+        assert_eq!(err.code, ErrorCode::QueryFailed);
+        assert!(err.message.starts_with("Unexpected query response <html>"));
+
+        // Valid JSON, but without the `data` field
+        let server = MockServer::start().await;
+        let response = ResponseTemplate::new(200).set_body_json(json!({ "result": {} }));
+        mock(&server, &smart_path(&msg), response).await;
+        let lcd = LcdClient::new(&server.uri()).unwrap();
+        let err = lcd.smart_query(CONTRACT, &msg).await.unwrap_err();
+        assert_eq!(err.code, ErrorCode::QueryFailed);
+    }
+
+    #[tokio::test]
+    async fn smart_query_times_out() {
+        let server = MockServer::start().await;
+        let msg = Binary::from(br#"{"owner_of":{"token_id":"1"}}"#);
+        let response = ResponseTemplate::new(200)
+            .set_body_json(json!({ "data": {} }))
+            .set_delay(Duration::from_secs(5));
+        mock(&server, &smart_path(&msg), response).await;
+
+        let lcd = LcdClient::with_timeout(&server.uri(), Duration::from_millis(100)).unwrap();
+        let err = lcd.smart_query(CONTRACT, &msg).await.unwrap_err();
+        assert_eq!(err.code, ErrorCode::QueryFailed);
+        assert!(err.message.starts_with("Error sending query to"));
+    }
+
+    #[tokio::test]
+    async fn latest_block_height_parses_int64_strings() {
+        let server = MockServer::start().await;
+        let blocks_latest = "/cosmos/base/tendermint/v1beta1/blocks/latest";
+        let response = ResponseTemplate::new(200)
+            .set_body_json(json!({ "block": { "header": { "height": "4321987" } } }));
+        mock(&server, blocks_latest, response).await;
+        let lcd = LcdClient::new(&server.uri()).unwrap();
+        assert_eq!(lcd.latest_block_height().await.unwrap(), 4321987);
+
+        let server = MockServer::start().await;
+        let response = ResponseTemplate::new(200)
+            .set_body_json(json!({ "block": { "header": { "height": "-1" } } }));
+        mock(&server, blocks_latest, response).await;
+        let lcd = LcdClient::new(&server.uri()).unwrap();
+        let err = lcd.latest_block_height().await.unwrap_err();
+        assert_eq!(err.message, "Unexpected block height -1");
+    }
+}
@@ -0,0 +1,33 @@
+// Ambur marketplace message builders, registry and MCP server. The ambur-mcp
+// binary serves `AmburMcp` over stdio or HTTP+SSE; Rust services can embed it,
+// or call its typed entry points (e.g. `AmburMcp::build_cosmos_msg` and
+// `AmburMcp::query_request`) for CosmosMsg and QueryRequest values directly
+
+pub mod address;
+pub mod contract;
+pub mod cw721;
+pub mod error;
+pub mod execute;
+pub mod expiration;
+pub mod family;
+pub mod instruction;
+pub mod lcd;
+pub mod network;
+pub mod payment;
+pub mod prompt;
+pub mod query;
+pub mod registry;
+pub mod resource;
+pub mod rules;
+pub mod schema;
+pub mod server;
+#[cfg(feature = "signing")]
+pub mod signer;
+pub mod tx;
+pub mod workflow;
+
+pub use address::{ContractMatch, ContractRole};
+pub use error::{AmburError, ErrorCode};
+pub use network::ArchwayNetwork;
+pub use registry::Registry;
+pub use server::ambur::AmburMcp;
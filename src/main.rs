@@ -1,22 +1,169 @@
-pub mod contract;
-pub mod execute;
-pub mod instruction;
-pub mod network;
-pub mod query;
-pub mod server;
-
-use rmcp::{ServiceExt, transport::stdio};
+use clap::{Parser, ValueEnum};
+use rmcp::{
+    ServiceExt,
+    transport::{sse_server::SseServer, stdio},
+};
 use std::error::Error as StdError;
+use std::io;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+#[cfg(unix)]
+use tokio::signal::unix::{SignalKind, signal};
+use tokio::task::JoinSet;
 
-use crate::server::ambur::AmburMcp;
+#[cfg(feature = "signing")]
+use ambur_mcp::signer;
+use ambur_mcp::{AmburMcp, Registry, schema};
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum Transport {
+    Stdio,
+    Sse,
+}
+
+#[derive(Parser, Debug)]
+#[command(version, about)]
+struct Args {
+    /// Path to a TOML or JSON contract and collection registry (defaults to the compiled-in registry)
+    #[arg(long, env = "AMBUR_REGISTRY")]
+    registry: Option<PathBuf>,
+    /// Transport used to serve MCP clients
+    #[arg(long, value_enum, default_value_t = Transport::Stdio, env = "AMBUR_TRANSPORT")]
+    transport: Transport,
+    /// Address the HTTP+SSE transport listens on
+    #[arg(long, default_value = "127.0.0.1:8000", env = "AMBUR_BIND")]
+    bind: SocketAddr,
+    /// Directory of cosmwasm-schema output (API files, or schema directories) for the collections' token and minter contracts, and for contracts the generic schema tools can build messages for
+    #[arg(long, env = "AMBUR_SCHEMA_DIR")]
+    schema_dir: Option<PathBuf>,
+    /// Enable tools that send queries to the LCD endpoints of the configured networks
+    #[arg(long, env = "AMBUR_LIVE_QUERIES")]
+    live_queries: bool,
+    /// Enable the sign_tx tool, signing with the key in --keyfile or derived from the AMBUR_MNEMONIC environment variable
+    #[cfg(feature = "signing")]
+    #[arg(long, env = "AMBUR_SIGNING")]
+    signing: bool,
+    /// Encrypted keyfile holding the signing key, unlocked with the AMBUR_KEYFILE_PASSPHRASE environment variable
+    #[cfg(feature = "signing")]
+    #[arg(long, env = "AMBUR_KEYFILE")]
+    keyfile: Option<PathBuf>,
+    /// HD path used to derive the signing key from a mnemonic
+    #[cfg(feature = "signing")]
+    #[arg(long, default_value = signer::ARCHWAY_HD_PATH, env = "AMBUR_HD_PATH")]
+    hd_path: String,
+    /// Encrypt the key derived from AMBUR_MNEMONIC into a new keyfile, using AMBUR_KEYFILE_PASSPHRASE, and exit
+    #[cfg(feature = "signing")]
+    #[arg(long, value_name = "PATH")]
+    create_keyfile: Option<PathBuf>,
+}
+
+// How long open SSE sessions are given to close on shutdown
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Ctrl-C, or the SIGTERM sent by containers and service managers
+async fn shutdown_signal() -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        let mut terminate = signal(SignalKind::terminate())?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result,
+            _ = terminate.recv() => Ok(()),
+        }
+    }
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await
+}
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn StdError>> {
-    let ambur_mcp = AmburMcp::new().serve(stdio()).await.inspect_err(|e| {
-        println!("{e}");
-    })?;
+    let args = Args::parse();
+
+    #[cfg(feature = "signing")]
+    if let Some(path) = &args.create_keyfile {
+        let signer = signer::create_keyfile(path, &args.hd_path)?;
+        eprintln!(
+            "Wrote keyfile {} for public key {}",
+            path.display(),
+            signer.public_key()
+        );
+        return Ok(());
+    }
+
+    let registry = match args.registry {
+        Some(path) => Registry::from_file(&path)?,
+        None => {
+            let registry = Registry::default();
+            registry.validate()?;
+            registry
+        }
+    };
+
+    let schemas = match args.schema_dir {
+        Some(dir) => schema::load_schema_dir(&dir)?,
+        None => vec![],
+    };
+
+    let ambur_mcp = AmburMcp::with_registry(registry)
+        .with_schemas(schemas)
+        .with_live_queries(args.live_queries);
+    // The signing key is only loaded when signing is explicitly enabled
+    #[cfg(feature = "signing")]
+    let ambur_mcp = if args.signing {
+        ambur_mcp.with_signer(signer::load_signer(args.keyfile.as_deref(), &args.hd_path)?)
+    } else {
+        ambur_mcp
+    };
+
+    match args.transport {
+        Transport::Stdio => {
+            let ambur_mcp = ambur_mcp.serve(stdio()).await.inspect_err(|e| {
+                println!("{e}");
+            })?;
+
+            ambur_mcp.waiting().await?;
+        }
+        Transport::Sse => {
+            let mut sse_server = SseServer::serve(args.bind).await?;
+            let ct = sse_server.config.ct.clone();
+            // Every SSE connection gets its own session, all sharing the same registry
+            let server = tokio::spawn({
+                let ct = ct.clone();
+                async move {
+                    let mut sessions = JoinSet::new();
+                    loop {
+                        tokio::select! {
+                            transport = sse_server.next_transport() => {
+                                let Some(transport) = transport else { break };
+                                let service = ambur_mcp.clone();
+                                let ct = ct.child_token();
+                                sessions.spawn(async move {
+                                    let session = service.serve_with_ct(transport, ct).await?;
+                                    session.waiting().await?;
+                                    io::Result::Ok(())
+                                });
+                            }
+                            _ = ct.cancelled() => break,
+                        }
+                    }
+                    // Cancelling closes every session, wait for them to finish
+                    while sessions.join_next().await.is_some() {}
+                }
+            });
 
-    ambur_mcp.waiting().await?;
+            shutdown_signal().await?;
+            ct.cancel();
+            if tokio::time::timeout(SHUTDOWN_TIMEOUT, server)
+                .await
+                .is_err()
+            {
+                eprintln!(
+                    "Open sessions didn't close within {}s, exiting anyway",
+                    SHUTDOWN_TIMEOUT.as_secs()
+                );
+            }
+        }
+    }
 
     Ok(())
 }
@@ -1,9 +1,59 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash, JsonSchema)]
 pub enum ArchwayNetwork {
     Mainnet,
     Constantine,
     Titus,
+    Local,
+    Custom(String),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NetworkConfig {
+    pub network: ArchwayNetwork,
+    pub chain_id: String,
+    pub bech32_prefix: String,
+    pub fee_denom: String,
+    pub rpc: Option<String>,
+    pub lcd: Option<String>,
+    // Average time between blocks, used to estimate when a block height is reached
+    #[serde(default)]
+    pub block_time_ms: Option<u64>,
+}
+
+// Archway produces a block roughly every 6 seconds
+static ARCHWAY_BLOCK_TIME_MS: u64 = 6000;
+
+pub fn default_networks() -> Vec<NetworkConfig> {
+    vec![
+        NetworkConfig {
+            network: ArchwayNetwork::Mainnet,
+            chain_id: "archway-1".to_string(),
+            bech32_prefix: "archway".to_string(),
+            fee_denom: "aarch".to_string(),
+            rpc: Some("https://rpc.mainnet.archway.io".to_string()),
+            lcd: Some("https://api.mainnet.archway.io".to_string()),
+            block_time_ms: Some(ARCHWAY_BLOCK_TIME_MS),
+        },
+        NetworkConfig {
+            network: ArchwayNetwork::Constantine,
+            chain_id: "constantine-3".to_string(),
+            bech32_prefix: "archway".to_string(),
+            fee_denom: "aconst".to_string(),
+            rpc: Some("https://rpc.constantine.archway.io".to_string()),
+            lcd: Some("https://api.constantine.archway.io".to_string()),
+            block_time_ms: Some(ARCHWAY_BLOCK_TIME_MS),
+        },
+        NetworkConfig {
+            network: ArchwayNetwork::Titus,
+            chain_id: "titus-4".to_string(),
+            bech32_prefix: "archway".to_string(),
+            fee_denom: "atitus".to_string(),
+            rpc: Some("https://rpc.titus.archway.io".to_string()),
+            lcd: Some("https://api.titus.archway.io".to_string()),
+            block_time_ms: Some(ARCHWAY_BLOCK_TIME_MS),
+        },
+    ]
 }
@@ -0,0 +1,445 @@
+use cosmwasm_std::{Coin, Uint128};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::contract::{USDC_DECIMALS, USDC_DENOM_MAINNET, WUSDC_MAINNET};
+use crate::error::{AmburError, ErrorCode};
+use crate::execute::rewrite_variant_field;
+use crate::network::ArchwayNetwork;
+use crate::registry::Registry;
+
+// Sale swaps are paid with native USDC, Offers and collection offers with wUSDC
+pub static SALE_PAYMENT_SYMBOL: &str = "USDC";
+pub static OFFER_PAYMENT_SYMBOL: &str = "wUSDC";
+// Precision of the chain's own 18 decimal native tokens (e.g. aarch)
+pub static MAX_DECIMALS: u32 = 18;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SwapPaymentToken {
+    Native { denom: String },
+    Cw20 { address: String },
+}
+
+impl SwapPaymentToken {
+    // The denom of a native token, or the contract address of a cw20 token
+    pub fn id(&self) -> &str {
+        match self {
+            SwapPaymentToken::Native { denom } => denom,
+            SwapPaymentToken::Cw20 { address } => address,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PaymentTokenConfig {
+    pub network: ArchwayNetwork,
+    pub symbol: String,
+    pub token: SwapPaymentToken,
+    pub decimals: u32,
+}
+
+impl PaymentTokenConfig {
+    pub fn to_base_units(&self, amount: &str) -> Result<Uint128, AmburError> {
+        to_base_units(amount, self.decimals)
+    }
+
+    // e.g. 8880000 -> "8.88 USDC"
+    pub fn format(&self, amount: Uint128) -> String {
+        format!("{} {}", from_base_units(amount, self.decimals), self.symbol)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ParsedAmount {
+    // Amount in base units, as sent in messages and funds
+    pub amount: Uint128,
+    // Human readable amount with its symbol (e.g. "8.88 USDC")
+    pub formatted: String,
+    pub payment_token: PaymentTokenConfig,
+}
+
+impl ParsedAmount {
+    pub fn new(amount: Uint128, config: &PaymentTokenConfig) -> Self {
+        Self {
+            amount,
+            formatted: config.format(amount),
+            payment_token: config.clone(),
+        }
+    }
+}
+
+pub fn default_payment_tokens() -> Vec<PaymentTokenConfig> {
+    vec![
+        PaymentTokenConfig {
+            network: ArchwayNetwork::Mainnet,
+            symbol: SALE_PAYMENT_SYMBOL.to_string(),
+            token: SwapPaymentToken::Native {
+                denom: USDC_DENOM_MAINNET.to_string(),
+            },
+            decimals: USDC_DECIMALS,
+        },
+        PaymentTokenConfig {
+            network: ArchwayNetwork::Mainnet,
+            symbol: OFFER_PAYMENT_SYMBOL.to_string(),
+            token: SwapPaymentToken::Cw20 {
+                address: WUSDC_MAINNET.to_string(),
+            },
+            decimals: USDC_DECIMALS,
+        },
+    ]
+}
+
+// Convert a decimal amount (e.g. "12.5") to base units of a token with `decimals`
+pub fn to_base_units(amount: &str, decimals: u32) -> Result<Uint128, AmburError> {
+    let invalid = || {
+        AmburError::new(
+            ErrorCode::InvalidAmount,
+            format!(
+                "Invalid amount {amount}, expected a decimal number with at most {decimals} decimals"
+            ),
+        )
+    };
+    let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    if (whole.is_empty() && fraction.is_empty())
+        || fraction.len() > decimals as usize
+        || !whole
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+    let digits = format!("{whole}{fraction:0<width$}", width = decimals as usize);
+    digits
+        .parse::<u128>()
+        .map(Uint128::new)
+        .map_err(|_| invalid())
+}
+
+pub fn from_base_units(amount: Uint128, decimals: u32) -> String {
+    let digits = format!("{:0>width$}", amount.u128(), width = decimals as usize + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals as usize);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{whole}.{fraction}")
+    }
+}
+
+// Split an amount into its number and optional symbol (e.g. "8.88 USDC" or "8.88")
+pub fn split_amount(input: &str) -> Result<(&str, Option<&str>), AmburError> {
+    let mut parts = input.split_whitespace();
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(amount), symbol, None) => Ok((amount, symbol)),
+        _ => Err(AmburError::new(
+            ErrorCode::InvalidAmount,
+            format!("Invalid amount {input}, expected an amount such as \"8.88 USDC\""),
+        )),
+    }
+}
+
+// Plain integers are base units, amounts with a decimal point or a symbol are human amounts
+fn is_base_units(amount: &str, symbol: Option<&str>) -> bool {
+    symbol.is_none() && !amount.is_empty() && amount.chars().all(|c| c.is_ascii_digit())
+}
+
+// Parse an amount into base units; every amount (prices, payments and
+// workflow inputs) goes through here. Plain integers already are base units;
+// human amounts ("8.88 USDC", or "8.88" when the expected payment token is
+// known) are converted with the registered token's decimals. With
+// `human_only`, for inputs documented as human amounts, plain integers are
+// refused since they could be either
+pub fn parse_payment_amount<'a>(
+    registry: &'a Registry,
+    input: &str,
+    network: Option<&ArchwayNetwork>,
+    expected: Option<&SwapPaymentToken>,
+    human_only: bool,
+) -> Result<(Uint128, Option<&'a PaymentTokenConfig>), AmburError> {
+    let (amount, symbol) = split_amount(input)?;
+    if human_only && is_base_units(amount, symbol) {
+        return Err(AmburError::new(
+            ErrorCode::InvalidAmount,
+            format!(
+                "Amount {input} is ambiguous, add the payment token's symbol or a decimal point (e.g. \"{input} USDC\" or \"{input}.0\")"
+            ),
+        ));
+    }
+    let config = match symbol {
+        Some(symbol) => Some(registry.payment_token(symbol, network)?),
+        None => expected.and_then(|token| registry.payment_token(token.id(), network).ok()),
+    };
+    if let (Some(config), Some(expected)) = (config, expected)
+        && &config.token != expected
+    {
+        let expected = registry
+            .payment_token(expected.id(), network)
+            .map(|expected| expected.symbol.as_str())
+            .unwrap_or(expected.id());
+        return Err(AmburError::new(
+            ErrorCode::InvalidAmount,
+            format!(
+                "Amount {input} is in {}, but the payment token is {expected}",
+                config.symbol
+            ),
+        ));
+    }
+    if is_base_units(amount, symbol) {
+        let amount = amount
+            .parse::<u128>()
+            .map_err(|_| AmburError::invalid_amount(input))?;
+        return Ok((Uint128::new(amount), config));
+    }
+    let Some(config) = config else {
+        return Err(AmburError::new(
+            ErrorCode::InvalidAmount,
+            format!(
+                "The decimals of amount {input} aren't known, add the payment token's symbol (e.g. \"8.88 USDC\") or give the amount in base units"
+            ),
+        ));
+    };
+    Ok((config.to_base_units(amount)?, Some(config)))
+}
+
+// Native funds from a base unit or human `payment`; `payment_denom` may be a
+// denom or the symbol of a registered native token, and can be left out when
+// the payment has a symbol
+pub fn parse_funds(
+    registry: &Registry,
+    network: Option<&ArchwayNetwork>,
+    payment: Option<String>,
+    payment_denom: Option<String>,
+) -> Result<Vec<Coin>, AmburError> {
+    let Some(payment) = payment else {
+        return Ok(vec![]);
+    };
+    let expected = payment_denom.map(|denom| match registry.payment_token(&denom, network) {
+        Ok(config) => config.token.clone(),
+        Err(_) => SwapPaymentToken::Native { denom },
+    });
+    let (amount, config) =
+        parse_payment_amount(registry, &payment, network, expected.as_ref(), false)?;
+    match config.map(|config| &config.token).or(expected.as_ref()) {
+        Some(SwapPaymentToken::Native { denom }) => Ok(vec![Coin {
+            denom: denom.clone(),
+            amount,
+        }]),
+        Some(SwapPaymentToken::Cw20 { address }) => Err(AmburError::new(
+            ErrorCode::InvalidAmount,
+            format!(
+                "cw20 token {address} can't be sent as native funds, allow the contract to spend it with a cw20 `increase_allowance` instead"
+            ),
+        )),
+        None => Err(AmburError::new(
+            ErrorCode::InvalidAmount,
+            format!("A payment_denom is required for the base unit payment {payment}"),
+        )),
+    }
+}
+
+// Convert a human `price` (e.g. "8.88 USDC", or "8.88" in the message's payment
+// token) in a marketplace ExecuteMsg to base units; prices already in base
+// units are kept
+pub fn normalize_price(
+    registry: &Registry,
+    network: Option<&ArchwayNetwork>,
+    execute_msg: &str,
+) -> Result<String, AmburError> {
+    rewrite_variant_field(execute_msg, "price", |fields| {
+        let Some(Value::String(price)) = fields.get("price") else {
+            return Ok(None);
+        };
+        let expected = fields
+            .get("payment_token")
+            .and_then(|token| serde_json::from_value::<SwapPaymentToken>(token.clone()).ok());
+        let (amount, _) = parse_payment_amount(registry, price, network, expected.as_ref(), false)?;
+        Ok(Some(Value::String(amount.to_string())))
+    })
+}
+
+// Only Mainnet's payment tokens are built in, the testnets' tokens must come from
+// the registry file
+fn network_payment_token<'a>(
+    registry: &'a Registry,
+    symbol: &str,
+    network: &ArchwayNetwork,
+) -> Result<&'a PaymentTokenConfig, AmburError> {
+    registry
+        .payment_token(symbol, Some(network))
+        .map_err(|err| match err.code {
+            ErrorCode::UnknownPaymentToken => AmburError::new(
+                ErrorCode::UnknownPaymentToken,
+                format!(
+                    "No {symbol} payment token configured for {network:?}, add it to the registry's payment_tokens (only Mainnet's USDC and wUSDC are built in)"
+                ),
+            ),
+            _ => err,
+        })
+}
+
+// The registered token Sale swaps are paid with, which must be native
+pub fn sale_payment_token<'a>(
+    registry: &'a Registry,
+    network: &ArchwayNetwork,
+) -> Result<&'a PaymentTokenConfig, AmburError> {
+    match network_payment_token(registry, SALE_PAYMENT_SYMBOL, network)? {
+        config @ PaymentTokenConfig {
+            token: SwapPaymentToken::Native { .. },
+            ..
+        } => Ok(config),
+        _ => Err(AmburError::new(
+            ErrorCode::InvalidRegistry,
+            format!(
+                "The {SALE_PAYMENT_SYMBOL} payment token on {network:?} must be a native token"
+            ),
+        )),
+    }
+}
+
+// The registered token Offers and collection offers are paid with, which must be a cw20
+pub fn offer_payment_token<'a>(
+    registry: &'a Registry,
+    network: &ArchwayNetwork,
+) -> Result<&'a PaymentTokenConfig, AmburError> {
+    match network_payment_token(registry, OFFER_PAYMENT_SYMBOL, network)? {
+        config @ PaymentTokenConfig {
+            token: SwapPaymentToken::Cw20 { .. },
+            ..
+        } => Ok(config),
+        _ => Err(AmburError::new(
+            ErrorCode::InvalidRegistry,
+            format!("The {OFFER_PAYMENT_SYMBOL} payment token on {network:?} must be a cw20 token"),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usdc() -> SwapPaymentToken {
+        SwapPaymentToken::Native {
+            denom: USDC_DENOM_MAINNET.to_string(),
+        }
+    }
+
+    fn parse(input: &str, human_only: bool) -> Result<Uint128, AmburError> {
+        let registry = Registry::default();
+        parse_payment_amount(
+            &registry,
+            input,
+            Some(&ArchwayNetwork::Mainnet),
+            Some(&usdc()),
+            human_only,
+        )
+        .map(|(amount, _)| amount)
+    }
+    #[test]
+    fn to_base_units_pads_and_refuses_extra_decimals() {
+        assert_eq!(to_base_units("8.88", 6).unwrap(), Uint128::new(8_880_000));
+        assert_eq!(to_base_units("12", 6).unwrap(), Uint128::new(12_000_000));
+        assert_eq!(to_base_units(".5", 6).unwrap(), Uint128::new(500_000));
+        assert_eq!(to_base_units("0.000001", 6).unwrap(), Uint128::new(1));
+        assert_eq!(to_base_units("7", 0).unwrap(), Uint128::new(7));
+        // Amounts are never rounded, finer amounts than the token allows are refused
+        assert!(to_base_units("0.0000001", 6).is_err());
+        assert!(to_base_units("1.5", 0).is_err());
+        for invalid in ["", ".", "-1", "1,5", "1.2.3", "1e6", " 1"] {
+            assert!(to_base_units(invalid, 6).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn to_base_units_refuses_overflowing_amounts() {
+        let max = u128::MAX.to_string();
+        assert_eq!(to_base_units(&max, 0).unwrap(), Uint128::MAX);
+        assert!(to_base_units(&format!("{max}0"), 0).is_err());
+        assert!(to_base_units(&max, 6).is_err());
+    }
+
+    #[test]
+    fn from_base_units_trims_trailing_zeros() {
+        assert_eq!(from_base_units(Uint128::new(8_880_000), 6), "8.88");
+        assert_eq!(from_base_units(Uint128::new(12_000_000), 6), "12");
+        assert_eq!(from_base_units(Uint128::new(1), 6), "0.000001");
+        assert_eq!(from_base_units(Uint128::zero(), 6), "0");
+        assert_eq!(from_base_units(Uint128::new(7), 0), "7");
+        assert_eq!(
+            from_base_units(Uint128::MAX, 6),
+            format!(
+                "{}.{}",
+                u128::MAX / 1_000_000,
+                (u128::MAX % 1_000_000).to_string().trim_end_matches('0')
+            )
+        );
+        for amount in ["8.88", "0.000001", "12", "340282366920938.463463"] {
+            let base_units = to_base_units(amount, 6).unwrap();
+            assert_eq!(from_base_units(base_units, 6), amount);
+        }
+    }
+
+    #[test]
+    fn parse_payment_amount_converts_human_amounts() {
+        assert_eq!(parse("8.88 USDC", false).unwrap(), Uint128::new(8_880_000));
+        assert_eq!(parse("8.88 usdc", false).unwrap(), Uint128::new(8_880_000));
+        assert_eq!(parse("8.88", false).unwrap(), Uint128::new(8_880_000));
+        assert_eq!(parse("12.0", false).unwrap(), Uint128::new(12_000_000));
+        assert_eq!(parse("12 USDC", false).unwrap(), Uint128::new(12_000_000));
+        // Another registered token than the expected one is refused
+        let err = parse("8.88 wUSDC", false).unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidAmount);
+        // Without a symbol or an expected token, the decimals aren't known
+        let registry = Registry::default();
+        assert!(parse_payment_amount(&registry, "8.88", None, None, false).is_err());
+    }
+
+    #[test]
+    fn parse_payment_amount_keeps_plain_integers_as_base_units() {
+        assert_eq!(parse("8880000", false).unwrap(), Uint128::new(8_880_000));
+        let registry = Registry::default();
+        assert_eq!(
+            parse_payment_amount(&registry, "12", None, None, false)
+                .unwrap()
+                .0,
+            Uint128::new(12)
+        );
+        // Unless the input is documented as a human amount, where they're ambiguous
+        let err = parse("12", true).unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidAmount);
+        assert!(err.message.contains("ambiguous"));
+        assert_eq!(parse("12 USDC", true).unwrap(), Uint128::new(12_000_000));
+        assert_eq!(parse("12.0", true).unwrap(), Uint128::new(12_000_000));
+    }
+
+    #[test]
+    fn normalize_price_uses_the_message_payment_token() {
+        let registry = Registry::default();
+        let msg = |price: &str| {
+            serde_json::json!({
+                "create": { "payment_token": { "native": { "denom": USDC_DENOM_MAINNET } }, "price": price }
+            })
+            .to_string()
+        };
+        for (price, expected) in [
+            ("8.88", "8880000"),
+            ("8.88 USDC", "8880000"),
+            ("8880000", "8880000"),
+        ] {
+            let normalized =
+                normalize_price(&registry, Some(&ArchwayNetwork::Mainnet), &msg(price)).unwrap();
+            let normalized: Value = serde_json::from_str(&normalized).unwrap();
+            assert_eq!(normalized["create"]["price"], expected, "{price}");
+        }
+        assert!(
+            normalize_price(
+                &registry,
+                Some(&ArchwayNetwork::Mainnet),
+                &msg("8.88 wUSDC")
+            )
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,367 @@
+use rmcp::model::{
+    GetPromptResult, JsonObject, Prompt, PromptArgument, PromptMessage, PromptMessageRole,
+};
+use serde_json::Value;
+
+use crate::address::ContractRole;
+use crate::error::{AmburError, ErrorCode};
+use crate::family::{collection_examples, contract_family};
+use crate::network::ArchwayNetwork;
+use crate::registry::Registry;
+use crate::resource::find_network;
+use crate::schema::ContractSchema;
+
+// Guided workflows, walking the model through the sequence of Ambur tools for a
+// common task. Contract addresses are filled in from the registry, so the model
+// doesn't have to look them up
+struct PromptSpec {
+    name: &'static str,
+    description: &'static str,
+    arguments: &'static [(&'static str, &'static str, bool)],
+    // Collection whose minter schema the prompt's tools rely on
+    requires_collection: Option<&'static str>,
+    text: fn(&PromptArgs, &Registry) -> Result<String, AmburError>,
+}
+
+const NETWORK_ARGUMENT: (&str, &str, bool) = (
+    "network",
+    "network configured in the registry (e.g. \"mainnet\", \"constantine\" or \"custom-<name>\"), defaults to mainnet",
+    false,
+);
+
+// Its description is completed with the collections' names when listed
+const NFT_ARGUMENT: (&str, &str, bool) = ("nft", "name of the NFT collection", true);
+
+static PROMPTS: [PromptSpec; 4] = [
+    PromptSpec {
+        name: "list-nft-for-sale",
+        description: "List an NFT for sale on the Ambur marketplace, paid in USDC",
+        arguments: &[
+            NFT_ARGUMENT,
+            ("token_id", "token id of the NFT to list for sale", true),
+            ("price", "sale price in USDC (e.g. \"12.5 USDC\")", true),
+            (
+                "expires",
+                "when the listing expires (e.g. \"in 7 days\"), defaults to never expiring",
+                false,
+            ),
+            NETWORK_ARGUMENT,
+            (
+                "sender",
+                "address of the seller, who signs the transaction",
+                false,
+            ),
+        ],
+        requires_collection: None,
+        text: list_nft_for_sale,
+    },
+    PromptSpec {
+        name: "make-collection-offer",
+        description: "Offer to buy one or more NFTs from a collection, regardless of their token ids, paid in wUSDC",
+        arguments: &[
+            NFT_ARGUMENT,
+            (
+                "quantity",
+                "number of NFTs from the collection the offer is for",
+                true,
+            ),
+            (
+                "price_per_nft",
+                "offered price per NFT in wUSDC (e.g. \"12.5 wUSDC\")",
+                true,
+            ),
+            (
+                "expires",
+                "when the offer expires (e.g. \"in 7 days\"), defaults to never expiring",
+                false,
+            ),
+            NETWORK_ARGUMENT,
+            (
+                "sender",
+                "address of the offerer, who signs the transaction",
+                false,
+            ),
+        ],
+        requires_collection: None,
+        text: make_collection_offer,
+    },
+    PromptSpec {
+        name: "mint-from-minter",
+        description: "Mint an NFT from a collection's minter contract, paying its mint price",
+        arguments: &[
+            NFT_ARGUMENT,
+            NETWORK_ARGUMENT,
+            (
+                "sender",
+                "address of the minter, who signs the transaction",
+                false,
+            ),
+        ],
+        requires_collection: None,
+        text: mint_from_minter,
+    },
+    PromptSpec {
+        name: "redeem-foresight-ticket",
+        description: "Redeem a Foresight Ticket NFT for a whitelist mint spot in a newly launching collection",
+        arguments: &[
+            (
+                "token_id",
+                "token id of the Foresight Ticket to redeem",
+                true,
+            ),
+            NETWORK_ARGUMENT,
+            (
+                "sender",
+                "address of the ticket's owner, who signs the transaction",
+                false,
+            ),
+        ],
+        requires_collection: Some("foresight"),
+        text: redeem_foresight_ticket,
+    },
+];
+
+// The prompts whose collections' minter schemas are loaded
+pub fn list_prompts(schemas: &[ContractSchema]) -> Vec<Prompt> {
+    PROMPTS
+        .iter()
+        .filter(|spec| {
+            spec.requires_collection
+                .is_none_or(|nft| contract_family(schemas, nft, ContractRole::Minter).is_ok())
+        })
+        .map(|spec| {
+            let arguments = spec
+                .arguments
+                .iter()
+                .map(|(name, description, required)| PromptArgument {
+                    name: name.to_string(),
+                    description: Some(match *name {
+                        "nft" => format!("{description} (e.g. {})", collection_examples()),
+                        _ => description.to_string(),
+                    }),
+                    required: Some(*required),
+                })
+                .collect();
+            Prompt::new(spec.name, Some(spec.description), Some(arguments))
+        })
+        .collect()
+}
+
+struct PromptArgs<'a> {
+    prompt: &'a str,
+    arguments: JsonObject,
+    schemas: &'a [ContractSchema],
+}
+
+impl PromptArgs<'_> {
+    fn optional(&self, name: &str) -> Option<String> {
+        match self.arguments.get(name)? {
+            Value::Null => None,
+            Value::String(value) if value.trim().is_empty() => None,
+            Value::String(value) => Some(value.trim().to_string()),
+            value => Some(value.to_string()),
+        }
+    }
+
+    fn required(&self, name: &str) -> Result<String, AmburError> {
+        self.optional(name).ok_or_else(|| {
+            AmburError::new(
+                ErrorCode::MissingPromptArgument,
+                format!("The {} prompt requires the '{name}' argument", self.prompt),
+            )
+        })
+    }
+
+    fn network<'r>(&self, registry: &'r Registry) -> Result<&'r ArchwayNetwork, AmburError> {
+        find_network(
+            registry,
+            &self.optional("network").unwrap_or("mainnet".to_string()),
+        )
+    }
+}
+
+// The network as it's passed to the tools, e.g. "Mainnet"
+fn network_param(network: &ArchwayNetwork) -> String {
+    serde_json::to_string(network).unwrap_or_default()
+}
+
+fn optional_param(name: &str, value: &Option<String>) -> String {
+    match value {
+        Some(value) => format!(", {name} \"{value}\""),
+        None => String::new(),
+    }
+}
+
+fn signing_steps(network: &ArchwayNetwork, sender: &Option<String>) -> String {
+    let sender = match sender {
+        Some(sender) => format!("sender \"{sender}\""),
+        None => "the sender address, which you should ask me for".to_string(),
+    };
+    format!(
+        "Once I've confirmed, build the transaction for signing with 'build_sign_doc' (network \
+        {}, {sender}, the 'cosmos_msgs' exactly as returned), asking me for the account number, \
+        sequence, fee and gas limit. If local signing is enabled, 'sign_tx' can sign it instead. \
+        Never broadcast the transaction yourself.",
+        network_param(network)
+    )
+}
+
+pub fn get_prompt(
+    name: &str,
+    arguments: Option<JsonObject>,
+    registry: &Registry,
+    schemas: &[ContractSchema],
+) -> Result<GetPromptResult, AmburError> {
+    let spec = PROMPTS
+        .iter()
+        .find(|spec| spec.name == name)
+        .ok_or_else(|| {
+            AmburError::new(
+                ErrorCode::UnknownPrompt,
+                format!("Unknown prompt {name}, see the server's prompts"),
+            )
+        })?;
+    if let Some(nft) = spec.requires_collection {
+        contract_family(schemas, nft, ContractRole::Minter)?;
+    }
+    let args = PromptArgs {
+        prompt: spec.name,
+        arguments: arguments.unwrap_or_default(),
+        schemas,
+    };
+    let text = (spec.text)(&args, registry)?;
+    Ok(GetPromptResult {
+        description: Some(spec.description.to_string()),
+        messages: vec![PromptMessage::new_text(PromptMessageRole::User, text)],
+    })
+}
+
+fn list_nft_for_sale(args: &PromptArgs, registry: &Registry) -> Result<String, AmburError> {
+    let (nft, token_id, price) = (
+        args.required("nft")?,
+        args.required("token_id")?,
+        args.required("price")?,
+    );
+    let (expires, sender) = (args.optional("expires"), args.optional("sender"));
+    let network = args.network(registry)?;
+    let (collection, token) = registry.token(&nft, network)?;
+    let marketplace = registry.marketplace(network)?;
+    let owner = sender.as_deref().unwrap_or("my address");
+    Ok(format!(
+        "I want to list {name} #{token_id} for sale on the Ambur marketplace on {network:?}, \
+        for {price}.\n\n\
+        The {name} token contract is {token} and the Ambur marketplace contract is \
+        {marketplace}.\n\n\
+        Walk me through it with the Ambur tools:\n\
+        1. If live queries are enabled, check that {owner} owns the NFT with \
+        'execute_token_query' (nft \"{name}\", contract_addr \"{token}\", query_msg \
+        {{\"owner_of\":{{\"token_id\":\"{token_id}\"}}}}).\n\
+        2. Call 'prepare_sale_listing' with nft \"{name}\", token_id \"{token_id}\", price \
+        \"{price}\", network {network_param}{expires}{sender_param}.\n\
+        3. Show me the listing's 'summary' and 'swap_id', and ask me to confirm it.\n\
+        4. {signing}",
+        name = collection.name,
+        token = token.contract_address,
+        marketplace = marketplace.contract_address,
+        network_param = network_param(network),
+        expires = optional_param("expires", &expires),
+        sender_param = optional_param("sender", &sender),
+        signing = signing_steps(network, &sender),
+    ))
+}
+
+fn make_collection_offer(args: &PromptArgs, registry: &Registry) -> Result<String, AmburError> {
+    let (nft, quantity, price_per_nft) = (
+        args.required("nft")?,
+        args.required("quantity")?,
+        args.required("price_per_nft")?,
+    );
+    let (expires, sender) = (args.optional("expires"), args.optional("sender"));
+    let network = args.network(registry)?;
+    let (collection, _) = registry.token(&nft, network)?;
+    let marketplace = registry.marketplace(network)?;
+    let wusdc = match registry.payment_token("wUSDC", Some(network)) {
+        Ok(wusdc) => format!(
+            " and the wUSDC payment token contract is {}",
+            wusdc.token.id()
+        ),
+        Err(_) => String::new(),
+    };
+    Ok(format!(
+        "I want to make a collection offer for {quantity} {name} NFTs on the Ambur marketplace \
+        on {network:?}, offering {price_per_nft} per NFT.\n\n\
+        The Ambur marketplace contract is {marketplace}{wusdc}.\n\n\
+        Walk me through it with the Ambur tools:\n\
+        1. Call 'prepare_collection_offer' with nft \"{name}\", quantity {quantity}, \
+        price_per_nft \"{price_per_nft}\", network {network_param}{expires}{sender_param}.\n\
+        2. Show me the offer's 'summary', including the total wUSDC the marketplace will be \
+        allowed to spend, and its 'swap_id', and ask me to confirm it.\n\
+        3. {signing}",
+        name = collection.name,
+        marketplace = marketplace.contract_address,
+        network_param = network_param(network),
+        expires = optional_param("expires", &expires),
+        sender_param = optional_param("sender", &sender),
+        signing = signing_steps(network, &sender),
+    ))
+}
+
+fn mint_from_minter(args: &PromptArgs, registry: &Registry) -> Result<String, AmburError> {
+    let nft = args.required("nft")?;
+    let sender = args.optional("sender");
+    let network = args.network(registry)?;
+    contract_family(args.schemas, &nft, ContractRole::Minter)?;
+    let (collection, minter) = registry.minter(&nft, network)?;
+    Ok(format!(
+        "I want to mint a {name} NFT on {network:?}.\n\n\
+        The {name} minter contract is {minter}.\n\n\
+        Walk me through it with the Ambur tools:\n\
+        1. Call 'list_minter_query_entry_points' with nft \"{name}\" to find the query \
+        returning the minter's configuration, and if live queries are enabled, run it with \
+        'execute_minter_query' (nft \"{name}\", contract_addr \"{minter}\") to learn the mint \
+        price and whether minting is open.\n\
+        2. Call 'list_minter_tx_entry_points' with nft \"{name}\" to find the mint entry point \
+        and its parameters.\n\
+        3. Call 'build_minter_execute_msg' with nft \"{name}\", contract_addr \"{minter}\", the \
+        mint execute_msg, the mint price from the minter's configuration in base units as \
+        'payment' and its denom as 'payment_denom'{sender_param}.\n\
+        4. Tell me what the mint will cost, and ask me to confirm it.\n\
+        5. {signing}",
+        name = collection.name,
+        minter = minter.contract_address,
+        sender_param = optional_param("sender", &sender),
+        signing = signing_steps(network, &sender),
+    ))
+}
+
+fn redeem_foresight_ticket(args: &PromptArgs, registry: &Registry) -> Result<String, AmburError> {
+    let token_id = args.required("token_id")?;
+    let sender = args.optional("sender");
+    let network = args.network(registry)?;
+    let (collection, token) = registry.token("foresight", network)?;
+    let (_, minter) = registry.minter("foresight", network)?;
+    let owner = sender.as_deref().unwrap_or("my address");
+    Ok(format!(
+        "I want to redeem my Foresight Ticket #{token_id} on {network:?}, for a whitelist \
+        mint spot in a newly launching collection.\n\n\
+        The Foresight Ticket token contract is {token} and its minter contract is \
+        {minter}.\n\n\
+        Walk me through it with the Ambur tools:\n\
+        1. If live queries are enabled, check that {owner} owns the ticket with \
+        'execute_token_query' (nft \"{name}\", contract_addr \"{token}\", query_msg \
+        {{\"owner_of\":{{\"token_id\":\"{token_id}\"}}}}).\n\
+        2. Call 'list_minter_tx_entry_points' with nft \"{name}\" to find the entry point that \
+        redeems a ticket, and its parameters. Don't guess the entry point's name.\n\
+        3. Call 'build_minter_execute_msg' with nft \"{name}\", contract_addr \"{minter}\" and \
+        the redeem execute_msg for token id \"{token_id}\"{sender_param}. If the minter has to \
+        be approved to take the ticket first, build the cw721 `approve` and the redeem message \
+        together with 'build_tx_bundle' instead, in that order.\n\
+        4. Explain what redeeming does to the ticket, and ask me to confirm it.\n\
+        5. {signing}",
+        name = collection.name,
+        token = token.contract_address,
+        minter = minter.contract_address,
+        sender_param = optional_param("sender", &sender),
+        signing = signing_steps(network, &sender),
+    ))
+}
@@ -1,8 +1,22 @@
+use base64::{
+    Engine,
+    engine::general_purpose::{STANDARD, URL_SAFE},
+};
+use cosmwasm_std::{Binary, to_json_binary};
+use cw20::Cw20QueryMsg;
 use philabs_cw721_marketplace::msg::{
     CollectionOfferDetailsResponse, CollectionRoyaltiesResponse, DetailsResponse, ListResponse,
+    QueryMsg,
 };
-use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use schemars::{JsonSchema, schema_for};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+use crate::address::{ContractMatch, ContractRole};
+use crate::cw721::{decode_token_response, token_return_type};
+use crate::error::{AmburError, ErrorCode, from_json_str, from_json_value};
+use crate::family::contract_family;
+use crate::schema::ContractSchema;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct AllResponse {
@@ -16,4 +30,298 @@ pub struct AllResponse {
 pub struct ValidatedQuery {
     pub query_msg: String,
     pub query_request: String,
+    pub contract: ContractMatch,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct QueryResult {
+    pub query_msg: String,
+    pub contract: ContractMatch,
+    pub response_type: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+    pub response: Value,
+}
+
+// Name of an (externally tagged) message variant, e.g. `details` for {"details":{"id":"..."}}
+pub fn msg_variant(msg: &Value) -> Option<String> {
+    match msg {
+        Value::Object(map) if map.len() == 1 => map.keys().next().cloned(),
+        Value::String(variant) => Some(variant.clone()),
+        _ => None,
+    }
+}
+
+// The marketplace QueryMsg docs end each variant's description with "Return type: <Type>"
+pub fn marketplace_return_type(variant: &str) -> Option<String> {
+    let schema = serde_json::to_value(schema_for!(QueryMsg)).ok()?;
+    let description = schema["oneOf"]
+        .as_array()?
+        .iter()
+        .find(|item| item["required"][0] == variant)?["description"]
+        .as_str()?
+        .to_string();
+    let return_type = description.split("Return type:").nth(1)?;
+    return_type
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .find(|word| !word.is_empty())
+        .map(str::to_string)
+}
+
+// Validate a marketplace response against its return type, normalizing it along the way.
+// Responses without a known return type are passed through unchanged
+pub fn decode_marketplace_response(response_type: &str, data: Value) -> Result<Value, AmburError> {
+    match response_type {
+        "ListResponse" => normalize::<ListResponse>(data),
+        "DetailsResponse" => normalize::<DetailsResponse>(data),
+        "CollectionRoyaltiesResponse" => normalize::<CollectionRoyaltiesResponse>(data),
+        "CollectionOfferDetailsResponse" => normalize::<CollectionOfferDetailsResponse>(data),
+        _ => Ok(data),
+    }
+}
+
+pub fn normalize<T: DeserializeOwned + Serialize>(data: Value) -> Result<Value, AmburError> {
+    let typed: T = from_json_value(data)?;
+    serde_json::to_value(&typed).map_err(|err| AmburError::serialization::<T>(err.to_string()))
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DecodedResponse {
+    pub query_msg: String,
+    pub response_type: Option<String>,
+    pub validated: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+    pub response: Value,
+}
+
+pub fn response_type(role: ContractRole, variant: &str) -> Option<String> {
+    match role {
+        ContractRole::Marketplace => marketplace_return_type(variant),
+        ContractRole::Token => token_return_type(variant),
+        ContractRole::Minter | ContractRole::Cw20 => None,
+    }
+}
+
+pub fn decode_response(
+    role: ContractRole,
+    response_type: &str,
+    data: Value,
+) -> Result<Value, AmburError> {
+    match role {
+        ContractRole::Marketplace => decode_marketplace_response(response_type, data),
+        ContractRole::Token => decode_token_response(response_type, data),
+        ContractRole::Minter | ContractRole::Cw20 => Ok(data),
+    }
+}
+
+// Deserialize `query_msg` as the QueryMsg of the contract kind (and collection)
+pub fn query_binary(
+    schemas: &[ContractSchema],
+    role: ContractRole,
+    nft: Option<&str>,
+    query_msg: &str,
+) -> Result<Binary, AmburError> {
+    let collection = || {
+        nft.ok_or_else(|| {
+            AmburError::new(
+                ErrorCode::UnknownCollection,
+                format!("An NFT collection name is required for {role:?} queries"),
+            )
+        })
+    };
+    match role {
+        ContractRole::Marketplace => to_json_binary(&from_json_str::<QueryMsg>(query_msg)?)
+            .map_err(|err| AmburError::serialization::<Binary>(err.to_string())),
+        ContractRole::Token | ContractRole::Minter => {
+            contract_family(schemas, collection()?, role)?.query_binary(query_msg)
+        }
+        ContractRole::Cw20 => to_json_binary(&from_json_str::<Cw20QueryMsg>(query_msg)?)
+            .map_err(|err| AmburError::serialization::<Binary>(err.to_string())),
+    }
+}
+
+// Infer the return type of `query_msg` and validate `data` against it
+pub fn decode_query(
+    role: ContractRole,
+    query_msg: &str,
+    data: Value,
+) -> Result<(Option<String>, Value), AmburError> {
+    let response_type = serde_json::from_str::<Value>(query_msg)
+        .ok()
+        .and_then(|msg| msg_variant(&msg))
+        .and_then(|variant| response_type(role, &variant));
+    let response = match &response_type {
+        Some(response_type) => decode_response(role, response_type, data)?,
+        None => data,
+    };
+    Ok((response_type, response))
+}
+
+pub fn response_notes(response_type: Option<&str>) -> Vec<String> {
+    match response_type {
+        Some("CollectionRoyaltiesResponse") => vec![
+            "fee_percentage is a whole number percentage between 0 and 30, not basis points"
+                .to_string(),
+        ],
+        Some("DetailsResponse") | Some("ListResponse") => vec![
+            "price is denominated in the smallest unit of the payment token (e.g. 6 decimals for USDC and wUSDC)"
+                .to_string(),
+        ],
+        Some(_) => vec![],
+        None => vec![
+            "The return type of this query isn't known, the response was passed through without validation"
+                .to_string(),
+        ],
+    }
+}
+
+// Query responses arrive either as JSON (optionally wrapped in an LCD `data` field),
+// or as the base64 encoded bytes of that JSON
+pub fn parse_raw_response(raw: &str) -> Result<Value, AmburError> {
+    let raw = raw.trim();
+    let parsed = serde_json::from_str::<Value>(raw).ok().or_else(|| {
+        let bytes = STANDARD
+            .decode(raw)
+            .or_else(|_| URL_SAFE.decode(raw))
+            .ok()?;
+        serde_json::from_slice::<Value>(&bytes).ok()
+    });
+    let parsed = parsed.ok_or_else(|| {
+        AmburError::new(
+            ErrorCode::InvalidJson,
+            "Query response is neither JSON nor base64 encoded JSON",
+        )
+    })?;
+    match parsed {
+        Value::Object(mut map) if map.len() == 1 && map.contains_key("data") => {
+            Ok(map.remove("data").unwrap_or_default())
+        }
+        // A base64 string inside a JSON string (e.g. a raw RPC `data` field)
+        Value::String(encoded) => parse_raw_response(&encoded).or(Ok(Value::String(encoded))),
+        parsed => Ok(parsed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn decoded(role: ContractRole, query_msg: Value, data: Value) -> (String, Value) {
+        let (response_type, response) = decode_query(role, &query_msg.to_string(), data).unwrap();
+        (response_type.unwrap(), response)
+    }
+
+    fn details() -> Value {
+        json!({
+            "creator": "archway1creator",
+            "contract": "archway1cw721",
+            "token_id": "427",
+            "payment_token": { "native": { "denom": "uusdc" } },
+            "price": "8880000",
+            "swap_type": "Sale",
+            "expires": { "never": {} }
+        })
+    }
+
+    #[test]
+    fn marketplace_responses_are_decoded_by_query() {
+        let (response_type, response) = decoded(
+            ContractRole::Marketplace,
+            json!({ "details": { "id": "3b1f6f8e" } }),
+            details(),
+        );
+        assert_eq!(response_type, "DetailsResponse");
+        assert_eq!(response, details());
+
+        let swaps = json!({ "swaps": ["3b1f6f8e", "9fb7be1f"] });
+        let (response_type, response) = decoded(
+            ContractRole::Marketplace,
+            json!({ "list": { "limit": 2 } }),
+            swaps.clone(),
+        );
+        assert_eq!(response_type, "ListResponse");
+        assert_eq!(response, swaps);
+    }
+
+    #[test]
+    fn token_responses_are_decoded_by_query() {
+        let tokens = json!({ "tokens": ["1", "427"] });
+        let (response_type, response) = decoded(
+            ContractRole::Token,
+            json!({ "tokens": { "owner": "archway1owner" } }),
+            tokens.clone(),
+        );
+        assert_eq!(response_type, "TokensResponse");
+        assert_eq!(response, tokens);
+
+        let owner_of = json!({
+            "owner": "archway1owner",
+            "approvals": [{ "spender": "archway1spender", "expires": { "at_height": 1234567 } }]
+        });
+        let (response_type, response) = decoded(
+            ContractRole::Token,
+            json!({ "owner_of": { "token_id": "427" } }),
+            owner_of.clone(),
+        );
+        assert_eq!(response_type, "OwnerOfResponse");
+        assert_eq!(response, owner_of);
+
+        // Minter queries have no known return type, and are passed through
+        let (response_type, response) = decode_query(
+            ContractRole::Minter,
+            r#"{"config":{}}"#,
+            json!({ "price": 5 }),
+        )
+        .unwrap();
+        assert_eq!(response_type, None);
+        assert_eq!(response, json!({ "price": 5 }));
+    }
+
+    #[test]
+    fn mismatched_responses_are_rejected_with_their_type() {
+        let cases = [
+            (
+                ContractRole::Marketplace,
+                json!({ "details": { "id": "3b1f6f8e" } }),
+                json!({ "swaps": [] }),
+                "DetailsResponse",
+                None,
+            ),
+            (
+                ContractRole::Marketplace,
+                json!({ "list": {} }),
+                json!({ "swaps": [5] }),
+                "ListResponse",
+                Some("swaps[0]"),
+            ),
+            (
+                ContractRole::Token,
+                json!({ "tokens": { "owner": "archway1owner" } }),
+                json!({ "tokens": "427" }),
+                "TokensResponse",
+                Some("tokens"),
+            ),
+            (
+                ContractRole::Token,
+                json!({ "owner_of": { "token_id": "427" } }),
+                json!({ "owner": 5, "approvals": [] }),
+                "OwnerOfResponse",
+                Some("owner"),
+            ),
+        ];
+        for (role, query_msg, data, response_type, path) in cases {
+            let err = decode_query(role, &query_msg.to_string(), data).unwrap_err();
+            assert_eq!(err.code, ErrorCode::InvalidMessage, "{response_type}");
+            assert!(
+                err.expected_type
+                    .as_deref()
+                    .is_some_and(|expected| expected.ends_with(response_type)),
+                "{:?}",
+                err.expected_type
+            );
+            assert_eq!(err.path.as_deref(), path, "{response_type}");
+        }
+    }
 }
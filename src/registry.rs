@@ -0,0 +1,694 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use crate::address::validate_address;
+use crate::contract::*;
+use crate::error::{AmburError, ErrorCode};
+use crate::family::same_collection;
+use crate::network::{ArchwayNetwork, NetworkConfig, default_networks};
+use crate::payment::{MAX_DECIMALS, PaymentTokenConfig, SwapPaymentToken, default_payment_tokens};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Registry {
+    #[serde(default)]
+    pub networks: Vec<NetworkConfig>,
+    #[serde(default)]
+    pub payment_tokens: Vec<PaymentTokenConfig>,
+    pub contracts: Vec<AmburContract>,
+    pub collections: Vec<AmburCollection>,
+}
+
+impl Registry {
+    // Registry files are TOML when the extension says so, JSON otherwise
+    pub fn from_file(path: &Path) -> Result<Self, AmburError> {
+        let contents = fs::read_to_string(path).map_err(|err| {
+            AmburError::new(
+                ErrorCode::InvalidRegistry,
+                format!("Error reading registry file {}: {err}", path.display()),
+            )
+        })?;
+        let registry: Registry = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => toml::from_str(&contents).map_err(|err| {
+                AmburError::new(
+                    ErrorCode::InvalidRegistry,
+                    format!("Error parsing registry file {}: {err}", path.display()),
+                )
+            })?,
+            _ => serde_json::from_str(&contents).map_err(|err| {
+                AmburError::new(
+                    ErrorCode::InvalidRegistry,
+                    format!("Error parsing registry file {}: {err}", path.display()),
+                )
+            })?,
+        };
+        let registry = registry
+            .with_default_networks()
+            .with_default_payment_tokens();
+        registry.validate()?;
+        Ok(registry)
+    }
+
+    // Networks from a registry file override the built-in network of the same
+    // name (keeping its block time when none is given); built-in networks that
+    // aren't mentioned are kept
+    pub fn with_default_networks(mut self) -> Self {
+        let mut networks = default_networks();
+        for mut config in self.networks {
+            match networks.iter_mut().find(|n| n.network == config.network) {
+                Some(existing) => {
+                    config.block_time_ms = config.block_time_ms.or(existing.block_time_ms);
+                    *existing = config
+                }
+                None => networks.push(config),
+            }
+        }
+        self.networks = networks;
+        self
+    }
+
+    // Payment tokens from a registry file override the built-in token with the
+    // same symbol on the same network
+    pub fn with_default_payment_tokens(mut self) -> Self {
+        let mut payment_tokens = default_payment_tokens();
+        for config in self.payment_tokens {
+            match payment_tokens.iter_mut().find(|t| {
+                t.network == config.network && t.symbol.eq_ignore_ascii_case(&config.symbol)
+            }) {
+                Some(existing) => *existing = config,
+                None => payment_tokens.push(config),
+            }
+        }
+        self.payment_tokens = payment_tokens;
+        self
+    }
+
+    pub fn network(&self, network: &ArchwayNetwork) -> Option<&NetworkConfig> {
+        self.networks.iter().find(|n| &n.network == network)
+    }
+
+    // The part of the registry on one network; collections without contracts on
+    // it are left out
+    pub fn for_network(&self, network: &ArchwayNetwork) -> Registry {
+        let on_network = |contracts: &[AmburContract]| -> Vec<AmburContract> {
+            contracts
+                .iter()
+                .filter(|contract| &contract.network == network)
+                .cloned()
+                .collect()
+        };
+        Registry {
+            networks: self.network(network).into_iter().cloned().collect(),
+            payment_tokens: self
+                .payment_tokens
+                .iter()
+                .filter(|token| &token.network == network)
+                .cloned()
+                .collect(),
+            contracts: on_network(&self.contracts),
+            collections: self
+                .collections
+                .iter()
+                .map(|collection| AmburCollection {
+                    contract_addresses: CollectionContract {
+                        token: on_network(&collection.contract_addresses.token),
+                        minter: on_network(&collection.contract_addresses.minter),
+                    },
+                    ..collection.clone()
+                })
+                .filter(|collection| {
+                    !collection.contract_addresses.token.is_empty()
+                        || !collection.contract_addresses.minter.is_empty()
+                })
+                .collect(),
+        }
+    }
+
+    // Collections are matched by name or by one of their aliases (e.g.
+    // "foresight"), see `same_collection`
+    pub fn collection(&self, nft: &str) -> Result<&AmburCollection, AmburError> {
+        self.collections
+            .iter()
+            .find(|c| same_collection(&c.name, nft))
+            .ok_or_else(|| AmburError::unknown_collection(nft))
+    }
+
+    // Payment tokens are matched by symbol (case-insensitive), denom or cw20
+    // address. The network is only needed when several networks match
+    pub fn payment_token(
+        &self,
+        token: &str,
+        network: Option<&ArchwayNetwork>,
+    ) -> Result<&PaymentTokenConfig, AmburError> {
+        let matches: Vec<&PaymentTokenConfig> = self
+            .payment_tokens
+            .iter()
+            .filter(|t| network.is_none_or(|network| &t.network == network))
+            .filter(|t| t.symbol.eq_ignore_ascii_case(token) || t.token.id() == token)
+            .collect();
+        match matches.as_slice() {
+            [config] => Ok(config),
+            [] => Err(AmburError::new(
+                ErrorCode::UnknownPaymentToken,
+                match network {
+                    Some(network) => {
+                        format!("{token} isn't a registered payment token on {network:?}")
+                    }
+                    None => format!("{token} isn't a registered payment token"),
+                },
+            )),
+            _ => Err(AmburError::new(
+                ErrorCode::InvalidNetwork,
+                format!("{token} is a payment token on more than one network, specify the network"),
+            )),
+        }
+    }
+
+    pub fn marketplace(&self, network: &ArchwayNetwork) -> Result<&AmburContract, AmburError> {
+        self.contracts
+            .iter()
+            .find(|c| &c.network == network)
+            .ok_or_else(|| {
+                AmburError::new(
+                    ErrorCode::InvalidNetwork,
+                    format!("No Ambur marketplace is registered on {network:?}"),
+                )
+            })
+    }
+
+    pub fn token(
+        &self,
+        nft: &str,
+        network: &ArchwayNetwork,
+    ) -> Result<(&AmburCollection, &AmburContract), AmburError> {
+        let collection = self.collection(nft)?;
+        let token = collection
+            .contract_addresses
+            .token
+            .iter()
+            .find(|c| &c.network == network)
+            .ok_or_else(|| {
+                AmburError::new(
+                    ErrorCode::InvalidNetwork,
+                    format!(
+                        "No {} token contract is registered on {network:?}",
+                        collection.name
+                    ),
+                )
+            })?;
+        Ok((collection, token))
+    }
+
+    pub fn minter(
+        &self,
+        nft: &str,
+        network: &ArchwayNetwork,
+    ) -> Result<(&AmburCollection, &AmburContract), AmburError> {
+        let collection = self.collection(nft)?;
+        let minter = collection
+            .contract_addresses
+            .minter
+            .iter()
+            .find(|c| &c.network == network)
+            .ok_or_else(|| {
+                AmburError::new(
+                    ErrorCode::InvalidNetwork,
+                    format!(
+                        "No {} minter contract is registered on {network:?}",
+                        collection.name
+                    ),
+                )
+            })?;
+        Ok((collection, minter))
+    }
+
+    pub fn validate(&self) -> Result<(), AmburError> {
+        let mut networks: HashSet<&ArchwayNetwork> = HashSet::new();
+        for config in &self.networks {
+            if !networks.insert(&config.network) {
+                return Err(invalid_registry(format!(
+                    "Network {:?} is configured more than once",
+                    config.network
+                )));
+            }
+            if config.chain_id.trim().is_empty() || config.bech32_prefix.trim().is_empty() {
+                return Err(invalid_registry(format!(
+                    "Network {:?} requires a chain_id and bech32_prefix",
+                    config.network
+                )));
+            }
+            if config.block_time_ms == Some(0) {
+                return Err(invalid_registry(format!(
+                    "Network {:?} has a block_time_ms of 0",
+                    config.network
+                )));
+            }
+        }
+        let mut tokens: HashSet<(ArchwayNetwork, String)> = HashSet::new();
+        for config in &self.payment_tokens {
+            let network = &config.network;
+            let symbol = config.symbol.trim();
+            let Some(network_config) = self.network(network) else {
+                return Err(invalid_registry(format!(
+                    "Payment token {symbol} is registered on {network:?}, which isn't a configured network"
+                )));
+            };
+            if symbol.is_empty() || symbol.contains(char::is_whitespace) {
+                return Err(invalid_registry(format!(
+                    "Payment token symbol '{symbol}' on {network:?} must be a single word"
+                )));
+            }
+            if config.decimals > MAX_DECIMALS {
+                return Err(invalid_registry(format!(
+                    "Payment token {symbol} on {network:?} has {} decimals, at most {MAX_DECIMALS} are supported",
+                    config.decimals
+                )));
+            }
+            match &config.token {
+                SwapPaymentToken::Native { denom } if !is_valid_denom(denom) => {
+                    return Err(invalid_registry(format!(
+                        "Payment token {symbol} on {network:?} has an invalid denom '{denom}'"
+                    )));
+                }
+                SwapPaymentToken::Native { .. } => {}
+                SwapPaymentToken::Cw20 { address } => {
+                    validate_address(address, &network_config.bech32_prefix).map_err(|err| {
+                        invalid_registry(format!(
+                            "Payment token {symbol} ({network:?}): {}",
+                            err.message
+                        ))
+                    })?;
+                }
+            }
+            if !tokens.insert((network.clone(), symbol.to_lowercase()))
+                || !tokens.insert((network.clone(), config.token.id().to_string()))
+            {
+                return Err(invalid_registry(format!(
+                    "Payment token {symbol} ({}) is registered more than once on {network:?}",
+                    config.token.id()
+                )));
+            }
+        }
+        let mut deployed: HashSet<(ArchwayNetwork, String)> = HashSet::new();
+        self.validate_deployments("Ambur marketplace", &self.contracts, &mut deployed)?;
+        let mut names: HashSet<String> = HashSet::new();
+        for collection in &self.collections {
+            let name = collection.name.trim();
+            if name.is_empty() {
+                return Err(invalid_registry(
+                    "Collection name cannot be empty".to_string(),
+                ));
+            }
+            if !names.insert(name.to_lowercase()) {
+                return Err(invalid_registry(format!(
+                    "Collection {name} is registered more than once"
+                )));
+            }
+            let addresses = &collection.contract_addresses;
+            self.validate_deployments(&format!("{name} token"), &addresses.token, &mut deployed)?;
+            self.validate_deployments(&format!("{name} minter"), &addresses.minter, &mut deployed)?;
+        }
+        Ok(())
+    }
+
+    // Each contract may be deployed once per configured network, and no address
+    // may be registered twice on the same network
+    fn validate_deployments(
+        &self,
+        label: &str,
+        contracts: &[AmburContract],
+        deployed: &mut HashSet<(ArchwayNetwork, String)>,
+    ) -> Result<(), AmburError> {
+        let mut networks: HashSet<&ArchwayNetwork> = HashSet::new();
+        for contract in contracts {
+            let network = &contract.network;
+            let Some(config) = self.network(network) else {
+                return Err(invalid_registry(format!(
+                    "{label} is deployed on {network:?}, which isn't a configured network"
+                )));
+            };
+            validate_address(&contract.contract_address, &config.bech32_prefix).map_err(|err| {
+                invalid_registry(format!("{label} ({network:?}): {}", err.message))
+            })?;
+            if !networks.insert(network) {
+                return Err(invalid_registry(format!(
+                    "{label} lists more than one {network:?} deployment"
+                )));
+            }
+            if !deployed.insert((network.clone(), contract.contract_address.clone())) {
+                return Err(invalid_registry(format!(
+                    "{label}: {} is registered more than once on {network:?}",
+                    contract.contract_address
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+// Denoms as the Cosmos SDK accepts them: a letter, then 2 to 127 letters,
+// digits or one of "/:._-" (e.g. "aarch" or "ibc/<hash>")
+fn is_valid_denom(denom: &str) -> bool {
+    denom.starts_with(|c: char| c.is_ascii_alphabetic())
+        && (3..=128).contains(&denom.len())
+        && denom
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "/:._-".contains(c))
+}
+
+fn invalid_registry(message: String) -> AmburError {
+    AmburError::new(ErrorCode::InvalidRegistry, message)
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self {
+            networks: default_networks(),
+            payment_tokens: default_payment_tokens(),
+            contracts: vec![
+                AmburContract {
+                    network: ArchwayNetwork::Mainnet,
+                    contract_address: CONTRACT_MAINNET.to_string(),
+                },
+                AmburContract {
+                    network: ArchwayNetwork::Constantine,
+                    contract_address: CONTRACT_CONSTANTINE.to_string(),
+                },
+            ],
+            collections: vec![
+                AmburCollection {
+                    name: "Archies".to_string(),
+                    description: ARCHIES_DESCR.to_string(),
+                    contract_addresses: CollectionContract {
+                        token: vec![
+                            AmburContract {
+                                network: ArchwayNetwork::Mainnet,
+                                contract_address: TOKEN_ARCHIES_MAINNET.to_string(),
+                            },
+                            AmburContract {
+                                network: ArchwayNetwork::Constantine,
+                                contract_address: TOKEN_ARCHIES_CONSTANTINE.to_string(),
+                            },
+                        ],
+                        minter: vec![
+                            AmburContract {
+                                network: ArchwayNetwork::Mainnet,
+                                contract_address: MINTER_ARCHIES_MAINNET.to_string(),
+                            },
+                            AmburContract {
+                                network: ArchwayNetwork::Constantine,
+                                contract_address: MINTER_ARCHIES_CONSTANTINE.to_string(),
+                            },
+                        ],
+                    },
+                },
+                AmburCollection {
+                    name: "The Foresight Ticket".to_string(),
+                    description: FORESIGHT_DESCR.to_string(),
+                    contract_addresses: CollectionContract {
+                        token: vec![
+                            AmburContract {
+                                network: ArchwayNetwork::Mainnet,
+                                contract_address: TOKEN_FORESIGHT_MAINNET.to_string(),
+                            },
+                            AmburContract {
+                                network: ArchwayNetwork::Constantine,
+                                contract_address: TOKEN_FORESIGHT_CONSTANTINE.to_string(),
+                            },
+                        ],
+                        minter: vec![
+                            AmburContract {
+                                network: ArchwayNetwork::Mainnet,
+                                contract_address: MINTER_FORESIGHT_MAINNET.to_string(),
+                            },
+                            AmburContract {
+                                network: ArchwayNetwork::Constantine,
+                                contract_address: MINTER_FORESIGHT_CONSTANTINE.to_string(),
+                            },
+                        ],
+                    },
+                },
+                AmburCollection {
+                    name: "Derpies".to_string(),
+                    description: DERPIES_DESCR.to_string(),
+                    contract_addresses: CollectionContract {
+                        token: vec![
+                            AmburContract {
+                                network: ArchwayNetwork::Mainnet,
+                                contract_address: TOKEN_DERPIES_MAINNET.to_string(),
+                            },
+                            AmburContract {
+                                network: ArchwayNetwork::Constantine,
+                                contract_address: TOKEN_DERPIES_CONSTANTINE.to_string(),
+                            },
+                        ],
+                        minter: vec![
+                            AmburContract {
+                                network: ArchwayNetwork::Mainnet,
+                                contract_address: MINTER_DERPIES_MAINNET.to_string(),
+                            },
+                            AmburContract {
+                                network: ArchwayNetwork::Constantine,
+                                contract_address: MINTER_DERPIES_CONSTANTINE.to_string(),
+                            },
+                        ],
+                    },
+                },
+                AmburCollection {
+                    name: "Ghouls".to_string(),
+                    description: GHOULS_DESCR.to_string(),
+                    contract_addresses: CollectionContract {
+                        token: vec![
+                            AmburContract {
+                                network: ArchwayNetwork::Mainnet,
+                                contract_address: TOKEN_GHOULS_MAINNET.to_string(),
+                            },
+                            AmburContract {
+                                network: ArchwayNetwork::Constantine,
+                                contract_address: TOKEN_GHOULS_CONSTANTINE.to_string(),
+                            },
+                        ],
+                        minter: vec![
+                            AmburContract {
+                                network: ArchwayNetwork::Mainnet,
+                                contract_address: MINTER_GHOULS_MAINNET.to_string(),
+                            },
+                            AmburContract {
+                                network: ArchwayNetwork::Constantine,
+                                contract_address: MINTER_GHOULS_CONSTANTINE.to_string(),
+                            },
+                        ],
+                    },
+                },
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_invalid(registry: &Registry, message: &str) {
+        let err = registry.validate().unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidRegistry);
+        assert!(err.message.contains(message), "{}", err.message);
+    }
+
+    fn payment_token(symbol: &str, token: SwapPaymentToken, decimals: u32) -> PaymentTokenConfig {
+        PaymentTokenConfig {
+            network: ArchwayNetwork::Mainnet,
+            symbol: symbol.to_string(),
+            token,
+            decimals,
+        }
+    }
+
+    fn native(denom: &str) -> SwapPaymentToken {
+        SwapPaymentToken::Native {
+            denom: denom.to_string(),
+        }
+    }
+
+    // A registry file of its own in the temp dir
+    fn from_toml(contents: &str) -> Result<Registry, AmburError> {
+        let path =
+            std::env::temp_dir().join(format!("ambur-registry-{}.toml", uuid::Uuid::new_v4()));
+        fs::write(&path, contents).unwrap();
+        let registry = Registry::from_file(&path);
+        let _ = fs::remove_file(&path);
+        registry
+    }
+
+    #[test]
+    fn the_default_registry_is_valid() {
+        Registry::default().validate().unwrap();
+    }
+
+    #[test]
+    fn duplicate_collections_and_addresses_are_rejected() {
+        let mut registry = Registry::default();
+        let mut archies = registry.collections[0].clone();
+        archies.name = " ARCHIES ".to_string();
+        registry.collections.push(archies);
+        assert_invalid(&registry, "Collection ARCHIES is registered more than once");
+
+        // Another collection's token address, on the same network
+        let mut registry = Registry::default();
+        registry.collections[1].contract_addresses.token[0].contract_address =
+            TOKEN_ARCHIES_MAINNET.to_string();
+        assert_invalid(&registry, "is registered more than once on Mainnet");
+
+        // The marketplace's address as a minter
+        let mut registry = Registry::default();
+        registry.collections[0].contract_addresses.minter[0].contract_address =
+            CONTRACT_MAINNET.to_string();
+        assert_invalid(&registry, "is registered more than once on Mainnet");
+
+        let mut registry = Registry::default();
+        registry.contracts.push(AmburContract {
+            network: ArchwayNetwork::Mainnet,
+            contract_address: CONTRACT_CONSTANTINE.to_string(),
+        });
+        assert_invalid(&registry, "lists more than one Mainnet deployment");
+
+        // The same address may be deployed on different networks
+        let mut registry = Registry::default();
+        registry.contracts[1].contract_address = CONTRACT_MAINNET.to_string();
+        registry.validate().unwrap();
+    }
+
+    #[test]
+    fn malformed_addresses_are_rejected() {
+        // A typo breaks the bech32 checksum
+        let mut typo = TOKEN_ARCHIES_MAINNET.to_string();
+        let last = if typo.ends_with('q') { "p" } else { "q" };
+        typo.replace_range(typo.len() - 1.., last);
+        for address in [typo.as_str(), "archway1", "not an address", ""] {
+            let mut registry = Registry::default();
+            registry.collections[0].contract_addresses.token[0].contract_address =
+                address.to_string();
+            assert_invalid(&registry, "Archies token (Mainnet)");
+        }
+
+        // A valid address for another chain
+        let mut registry = Registry::default();
+        for config in &mut registry.networks {
+            if config.network == ArchwayNetwork::Constantine {
+                config.bech32_prefix = "const".to_string();
+            }
+        }
+        assert_invalid(&registry, "expected 'const'");
+
+        let mut registry = Registry::default();
+        registry.payment_tokens.push(payment_token(
+            "wATOM",
+            SwapPaymentToken::Cw20 {
+                address: typo.clone(),
+            },
+            6,
+        ));
+        assert_invalid(&registry, "Payment token wATOM (Mainnet)");
+    }
+
+    #[test]
+    fn inconsistent_payment_tokens_are_rejected() {
+        let usdc = Registry::default()
+            .payment_token("USDC", Some(&ArchwayNetwork::Mainnet))
+            .unwrap()
+            .clone();
+
+        // USDC's denom under another symbol, with other decimals
+        let mut registry = Registry::default();
+        registry
+            .payment_tokens
+            .push(payment_token("USDC18", usdc.token.clone(), 18));
+        assert_invalid(&registry, "is registered more than once on Mainnet");
+
+        // Another denom under USDC's symbol
+        let mut registry = Registry::default();
+        registry
+            .payment_tokens
+            .push(payment_token("usdc", native("uusdc"), 6));
+        assert_invalid(
+            &registry,
+            "Payment token usdc (uusdc) is registered more than once",
+        );
+
+        let mut registry = Registry::default();
+        registry
+            .payment_tokens
+            .push(payment_token("ARCH", native("aarch"), MAX_DECIMALS + 1));
+        assert_invalid(&registry, "has 19 decimals, at most 18 are supported");
+
+        for denom in ["", "u", "1arch", "a arch", "ibc/ABC DEF"] {
+            let mut registry = Registry::default();
+            registry
+                .payment_tokens
+                .push(payment_token("ARCH", native(denom), 18));
+            assert_invalid(&registry, "has an invalid denom");
+        }
+
+        let mut registry = Registry::default();
+        registry
+            .payment_tokens
+            .push(payment_token("ARCH", native("aarch"), 18));
+        registry.validate().unwrap();
+    }
+
+    #[test]
+    fn registry_files_are_parsed_and_validated() {
+        let registry = from_toml(
+            r#"
+contracts = []
+collections = []
+
+[[payment_tokens]]
+network = "Mainnet"
+symbol = "ARCH"
+token = { native = { denom = "aarch" } }
+decimals = 18
+"#,
+        )
+        .unwrap();
+        assert_eq!(registry.networks.len(), default_networks().len());
+        assert!(
+            registry
+                .payment_token("ARCH", Some(&ArchwayNetwork::Mainnet))
+                .is_ok()
+        );
+
+        let err = from_toml("contracts = [\n  { network = \"Mainnet\" ").unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidRegistry);
+        assert!(
+            err.message.contains("Error parsing registry file"),
+            "{}",
+            err.message
+        );
+
+        let err = from_toml("contracts = []\ncollections = \"Archies\"\n").unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidRegistry);
+        assert!(
+            err.message.contains("Error parsing registry file"),
+            "{}",
+            err.message
+        );
+
+        // Parsed files are validated
+        let err = from_toml(
+            r#"
+contracts = [{ network = "Mainnet", contract_address = "archway1invalid" }]
+collections = []
+"#,
+        )
+        .unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidRegistry);
+        assert!(
+            err.message.contains("Ambur marketplace (Mainnet)"),
+            "{}",
+            err.message
+        );
+    }
+}
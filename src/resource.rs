@@ -0,0 +1,337 @@
+use philabs_cw721_marketplace::msg::{ExecuteMsg, QueryMsg};
+use rmcp::model::{AnnotateAble, RawResource, RawResourceTemplate, Resource, ResourceTemplate};
+use schemars::schema_for;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::address::ContractRole;
+use crate::error::{AmburError, ErrorCode};
+use crate::family::{COLLECTION_FAMILIES, contract_family};
+use crate::network::ArchwayNetwork;
+use crate::query::AllResponse;
+use crate::registry::Registry;
+use crate::schema::{ContractSchema, MsgKind, find_schema};
+
+pub static RESOURCE_MIME_TYPE: &str = "application/json";
+
+// Percent-encode everything but the unreserved characters of RFC 3986, so any
+// name can be used as a URI path segment
+fn uri_segment(name: &str) -> String {
+    name.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            byte => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+// The name of a network in resource URIs, e.g. "mainnet". Custom networks are
+// prefixed, so a custom network named "mainnet" is "custom-mainnet"
+pub fn network_slug(network: &ArchwayNetwork) -> String {
+    match network {
+        ArchwayNetwork::Custom(name) => format!("custom-{}", uri_segment(name)),
+        network => format!("{network:?}").to_lowercase(),
+    }
+}
+
+// A network configured in the registry, by its slug (case-insensitive for the
+// built-in networks, custom network names keep their case)
+pub fn find_network<'a>(
+    registry: &'a Registry,
+    slug: &str,
+) -> Result<&'a ArchwayNetwork, AmburError> {
+    registry
+        .networks
+        .iter()
+        .map(|config| &config.network)
+        .find(|configured| match configured {
+            ArchwayNetwork::Custom(_) => network_slug(configured) == slug,
+            _ => network_slug(configured) == slug.to_lowercase(),
+        })
+        .ok_or_else(|| {
+            AmburError::new(
+                ErrorCode::InvalidNetwork,
+                format!("Network {slug} isn't configured in the registry"),
+            )
+        })
+}
+
+fn resource(uri: String, name: String, description: String) -> Resource {
+    RawResource {
+        description: Some(description),
+        mime_type: Some(RESOURCE_MIME_TYPE.to_string()),
+        ..RawResource::new(uri, name)
+    }
+    .no_annotation()
+}
+
+fn template(uri_template: &str, name: &str, description: &str) -> ResourceTemplate {
+    RawResourceTemplate {
+        uri_template: uri_template.to_string(),
+        name: name.to_string(),
+        description: Some(description.to_string()),
+        mime_type: Some(RESOURCE_MIME_TYPE.to_string()),
+    }
+    .no_annotation()
+}
+
+// Every schema and registry resource the server can read: the marketplace's
+// messages, the token and minter messages of each collection (minters only when
+// their schema is loaded), the loaded contract schemas, and the registry of each
+// configured network
+pub fn list_resources(registry: &Registry, schemas: &[ContractSchema]) -> Vec<Resource> {
+    let mut resources = vec![
+        resource(
+            "ambur://schema/marketplace/query".to_string(),
+            "Marketplace QueryMsg".to_string(),
+            "JSON schema of the Ambur marketplace's query entry points".to_string(),
+        ),
+        resource(
+            "ambur://schema/marketplace/execute".to_string(),
+            "Marketplace ExecuteMsg".to_string(),
+            "JSON schema of the Ambur marketplace's execute entry points".to_string(),
+        ),
+        resource(
+            "ambur://schema/marketplace/responses".to_string(),
+            "Marketplace query responses".to_string(),
+            "JSON schema of the Ambur marketplace's query responses".to_string(),
+        ),
+    ];
+    for family in COLLECTION_FAMILIES {
+        let collection = family.names[0];
+        let slug = family.slug;
+        for (role, role_name) in [
+            (ContractRole::Token, "token"),
+            (ContractRole::Minter, "minter"),
+        ] {
+            if contract_family(schemas, slug, role).is_err() {
+                continue;
+            }
+            for kind in ["query", "execute"] {
+                resources.push(resource(
+                    format!("ambur://schema/{slug}/{role_name}/{kind}"),
+                    format!("{collection} {role_name} {kind} schema"),
+                    format!(
+                        "JSON schema of the {kind} entry points of {collection}'s {role:?} contract"
+                    ),
+                ));
+            }
+        }
+    }
+    for schema in schemas {
+        for kind in schema.summary().msgs {
+            let kind = serde_json::to_value(kind).unwrap_or_default();
+            let kind = kind.as_str().unwrap_or_default();
+            resources.push(resource(
+                format!("ambur://schema/contract/{}/{kind}", schema.contract_name),
+                format!("{} {kind} schema", schema.contract_name),
+                format!(
+                    "JSON schema of the {kind} entry points of the loaded {} contract",
+                    schema.contract_name
+                ),
+            ));
+        }
+    }
+    resources.push(resource(
+        "ambur://registry".to_string(),
+        "Registry".to_string(),
+        "Networks, payment tokens and contract addresses of every configured network".to_string(),
+    ));
+    for network in &registry.networks {
+        let slug = network_slug(&network.network);
+        resources.push(resource(
+            format!("ambur://registry/{slug}"),
+            format!("{slug} registry"),
+            format!(
+                "Network config, payment tokens and contract addresses on {:?}",
+                network.network
+            ),
+        ));
+    }
+    resources
+}
+
+pub fn list_resource_templates() -> Vec<ResourceTemplate> {
+    vec![
+        template(
+            "ambur://schema/{collection}/{contract}/{kind}",
+            "Collection contract schema",
+            "JSON schema of an NFT collection's token or minter contract messages; contract is \"token\" or \"minter\", kind is \"query\" or \"execute\"",
+        ),
+        template(
+            "ambur://schema/contract/{name}/{kind}",
+            "Loaded contract schema",
+            "JSON schema of a contract loaded with --schema-dir; kind is \"instantiate\", \"execute\", \"query\", \"migrate\" or \"sudo\"",
+        ),
+        template(
+            "ambur://registry/{network}",
+            "Network registry",
+            "Network config, payment tokens and contract addresses on one network (e.g. \"mainnet\", or \"custom-<name>\" for a custom network)",
+        ),
+    ]
+}
+
+fn unknown_resource(uri: &str) -> AmburError {
+    AmburError::new(
+        ErrorCode::UnknownResource,
+        format!("Unknown resource {uri}, see the server's resources and resource templates"),
+    )
+}
+
+fn to_value<T: Serialize>(value: &T) -> Result<Value, AmburError> {
+    serde_json::to_value(value).map_err(|err| AmburError::serialization::<T>(err.to_string()))
+}
+
+// The JSON content of a resource
+pub fn read_resource(
+    uri: &str,
+    registry: &Registry,
+    schemas: &[ContractSchema],
+) -> Result<Value, AmburError> {
+    let path = uri
+        .strip_prefix("ambur://")
+        .ok_or_else(|| unknown_resource(uri))?;
+    let segments: Vec<&str> = path.split('/').collect();
+    match segments.as_slice() {
+        ["schema", "marketplace", "query"] => to_value(&schema_for!(QueryMsg)),
+        ["schema", "marketplace", "execute"] => to_value(&schema_for!(ExecuteMsg)),
+        ["schema", "marketplace", "responses"] => to_value(&schema_for!(AllResponse)),
+        ["schema", "contract", name, kind] => {
+            let kind: MsgKind = serde_json::from_value(Value::String(kind.to_string()))
+                .map_err(|_| unknown_resource(uri))?;
+            Ok(find_schema(schemas, name)?
+                .required_msg_schema(kind)?
+                .clone())
+        }
+        ["schema", collection, contract, kind] => {
+            let role = match *contract {
+                "token" => ContractRole::Token,
+                "minter" => ContractRole::Minter,
+                _ => return Err(unknown_resource(uri)),
+            };
+            let family = contract_family(schemas, collection, role)?;
+            match *kind {
+                "query" => family.query_schema(),
+                "execute" => family.execute_schema(),
+                _ => Err(unknown_resource(uri)),
+            }
+        }
+        ["registry"] => to_value(registry),
+        ["registry", network] => to_value(&registry.for_network(find_network(registry, network)?)),
+        _ => Err(unknown_resource(uri)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cw721::{Cw721ExecuteMsg, Cw721QueryMsg};
+    use crate::network::{NetworkConfig, default_networks};
+    use serde_json::json;
+
+    fn custom_network(name: &str) -> NetworkConfig {
+        NetworkConfig {
+            network: ArchwayNetwork::Custom(name.to_string()),
+            chain_id: format!("{name}-1"),
+            ..default_networks()[0].clone()
+        }
+    }
+
+    // The built-in networks, and custom networks whose names aren't URL-safe or
+    // collide with a built-in network's
+    fn registry() -> Registry {
+        let mut registry = Registry::default();
+        registry.networks.extend([
+            custom_network("mainnet"),
+            custom_network("My Devnet"),
+            custom_network("dev/net?#1"),
+        ]);
+        registry
+    }
+
+    // A loaded minter schema of every collection, and one contract schema
+    fn schemas() -> Vec<ContractSchema> {
+        let minter = |contract_name: &str| {
+            serde_json::from_value(json!({
+                "contract_name": contract_name,
+                "query": schema_for!(Cw721QueryMsg),
+                "execute": schema_for!(Cw721ExecuteMsg),
+            }))
+            .unwrap()
+        };
+        let mut schemas: Vec<ContractSchema> = COLLECTION_FAMILIES
+            .iter()
+            .map(|family| minter(family.minter_schema))
+            .collect();
+        schemas.push(minter("splitter"));
+        schemas
+    }
+
+    #[test]
+    fn listed_uris_are_valid_and_readable() {
+        let registry = registry();
+        let schemas = schemas();
+        let resources = list_resources(&registry, &schemas);
+        for resource in &resources {
+            let uri = reqwest::Url::parse(&resource.uri).unwrap();
+            assert_eq!(
+                uri.as_str(),
+                resource.uri,
+                "{} isn't a valid URI",
+                resource.uri
+            );
+            assert!(
+                read_resource(uri.as_str(), &registry, &schemas).is_ok(),
+                "{} can't be read",
+                resource.uri
+            );
+        }
+        for family in COLLECTION_FAMILIES {
+            for contract in ["token", "minter"] {
+                let uri = format!("ambur://schema/{}/{contract}/query", family.slug);
+                assert!(
+                    resources.iter().any(|resource| resource.uri == uri),
+                    "{uri}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn custom_networks_have_their_own_slugs() {
+        let registry = registry();
+        assert_eq!(network_slug(&ArchwayNetwork::Mainnet), "mainnet");
+        assert_eq!(
+            network_slug(&ArchwayNetwork::Custom("My Devnet".to_string())),
+            "custom-My%20Devnet"
+        );
+        for network in &registry.networks {
+            let slug = network_slug(&network.network);
+            assert_eq!(find_network(&registry, &slug).unwrap(), &network.network);
+            let uri = format!("ambur://registry/{slug}");
+            let read = read_resource(&uri, &registry, &[]).unwrap();
+            assert_eq!(
+                read,
+                to_value(&registry.for_network(&network.network)).unwrap()
+            );
+        }
+        assert_eq!(
+            find_network(&registry, "Mainnet").unwrap(),
+            &ArchwayNetwork::Mainnet
+        );
+        assert_eq!(
+            find_network(&registry, "custom-mainnet").unwrap(),
+            &ArchwayNetwork::Custom("mainnet".to_string())
+        );
+    }
+
+    #[test]
+    fn collection_slugs_are_url_safe_names() {
+        for family in COLLECTION_FAMILIES {
+            assert_eq!(uri_segment(family.slug), family.slug);
+            assert!(family.names.contains(&family.slug), "{}", family.slug);
+        }
+    }
+}
@@ -0,0 +1,514 @@
+use chrono::Utc;
+use cosmwasm_std::{Timestamp, Uint128};
+use cw_utils::Expiration;
+use philabs_cw721_marketplace::msg::ExecuteMsg;
+use serde_json::{Map, Value};
+
+use crate::address::{ContractRole, lookup_address};
+use crate::error::{AmburError, ErrorCode, from_json_value};
+use crate::expiration::to_timestamp;
+use crate::network::ArchwayNetwork;
+use crate::payment::{SwapPaymentToken, offer_payment_token, sale_payment_token};
+use crate::registry::Registry;
+use crate::workflow::{EXAMPLE_SWAP_IDS, SwapDetails};
+
+// Royalty fees are whole percentages, not basis points
+static MAX_FEE_PERCENTAGE: u64 = 30;
+
+// Business rules the marketplace contract enforces on-chain, checked before a
+// message is built so that a doomed tx is never signed. The payment token
+// addresses are only compared on networks where they are known
+pub struct MarketplaceRules<'a> {
+    registry: &'a Registry,
+    network: Option<ArchwayNetwork>,
+    now: Timestamp,
+}
+
+fn violation(path: &str, message: impl Into<String>) -> AmburError {
+    AmburError {
+        path: Some(path.to_string()),
+        ..AmburError::new(ErrorCode::RuleViolation, message)
+    }
+}
+
+// The variant name and fields of an externally tagged ExecuteMsg
+fn variant(msg: &ExecuteMsg) -> Result<(String, Map<String, Value>), AmburError> {
+    let value = serde_json::to_value(msg)
+        .map_err(|err| AmburError::serialization::<ExecuteMsg>(err.to_string()))?;
+    match value {
+        Value::Object(map) if map.len() == 1 => {
+            let (name, fields) = map.into_iter().next().unwrap_or_default();
+            match fields {
+                Value::Object(fields) => Ok((name, fields)),
+                _ => Ok((name, Map::new())),
+            }
+        }
+        Value::String(name) => Ok((name, Map::new())),
+        _ => Err(AmburError::serialization::<ExecuteMsg>(
+            "Unexpected ExecuteMsg representation",
+        )),
+    }
+}
+
+fn field<T: serde::de::DeserializeOwned>(
+    fields: &Map<String, Value>,
+    variant: &str,
+    name: &str,
+) -> Result<Option<T>, AmburError> {
+    match fields.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => from_json_value::<T>(value.clone())
+            .map(Some)
+            .map_err(|err| AmburError {
+                path: Some(format!("{variant}.{name}")),
+                ..err
+            }),
+    }
+}
+
+impl<'a> MarketplaceRules<'a> {
+    pub fn new(registry: &'a Registry, network: Option<ArchwayNetwork>) -> Self {
+        Self {
+            registry,
+            network,
+            now: to_timestamp(Utc::now()),
+        }
+    }
+
+    pub fn check(&self, msg: &ExecuteMsg) -> Result<(), AmburError> {
+        let (name, fields) = variant(msg)?;
+        match name.as_str() {
+            "create" => {
+                let swap_type: Option<String> = field(&fields, &name, "swap_type")?;
+                let payment_token = field(&fields, &name, "payment_token")?;
+                match (swap_type.as_deref(), payment_token) {
+                    (Some("Sale"), Some(payment_token)) => {
+                        self.check_sale_payment(&name, &payment_token)?
+                    }
+                    (Some("Offer"), Some(payment_token)) => {
+                        self.check_offer_payment(&name, "Offer swaps", &payment_token)?
+                    }
+                    _ => {}
+                }
+                check_new_id(&name, &fields)?;
+                self.check_cw721(&name, &fields)?;
+                self.check_expires(&name, &fields)?;
+            }
+            "create_collection_offer" => {
+                if let Some(payment_token) = field(&fields, &name, "payment_token")? {
+                    self.check_offer_payment(&name, "Collection offers", &payment_token)?;
+                }
+                check_new_id(&name, &fields)?;
+                self.check_cw721(&name, &fields)?;
+                self.check_expires(&name, &fields)?;
+            }
+            "update" => self.check_expires(&name, &fields)?,
+            _ => {}
+        }
+        check_fee_percentages(&name, &Value::Object(fields))
+    }
+
+    // The id of the swap an `update` message changes, its current details are
+    // needed to check the update
+    pub fn updated_swap(&self, msg: &ExecuteMsg) -> Result<Option<String>, AmburError> {
+        let (name, fields) = variant(msg)?;
+        if name != "update" {
+            return Ok(None);
+        }
+        field(&fields, &name, "id")
+    }
+
+    // The path and id of the swap a `create` or `create_collection_offer`
+    // message makes, which must be unused
+    pub fn created_swap(&self, msg: &ExecuteMsg) -> Result<Option<(String, String)>, AmburError> {
+        let (name, fields) = variant(msg)?;
+        if name != "create" && name != "create_collection_offer" {
+            return Ok(None);
+        }
+        Ok(field::<String>(&fields, &name, "id")?.map(|id| (format!("{name}.id"), id)))
+    }
+
+    // Only the expiration of an Offer can be updated, its price is fixed
+    pub fn check_update(&self, msg: &ExecuteMsg, current: &SwapDetails) -> Result<(), AmburError> {
+        let (name, fields) = variant(msg)?;
+        if name != "update" || current.swap_type != "Offer" {
+            return Ok(());
+        }
+        match field::<Uint128>(&fields, &name, "price")? {
+            Some(price) if price != current.price => Err(violation(
+                "update.price",
+                format!(
+                    "Only the expiration of an Offer can be updated, the price must stay {}",
+                    current.price
+                ),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_sale_payment(
+        &self,
+        variant: &str,
+        payment_token: &SwapPaymentToken,
+    ) -> Result<(), AmburError> {
+        let path = format!("{variant}.payment_token");
+        let usdc = self
+            .network
+            .as_ref()
+            .and_then(|network| sale_payment_token(self.registry, network).ok())
+            .map(|config| config.token.id());
+        match payment_token {
+            SwapPaymentToken::Cw20 { .. } => Err(violation(
+                &path,
+                "Sale swaps must be paid with Archway Network's native USDC, not a cw20 token",
+            )),
+            SwapPaymentToken::Native { denom } if usdc.is_some_and(|usdc| usdc != denom) => {
+                Err(violation(
+                    &path,
+                    format!(
+                        "Sale swaps must be paid with Archway Network's native USDC ({}), not {denom}",
+                        usdc.unwrap_or_default()
+                    ),
+                ))
+            }
+            SwapPaymentToken::Native { .. } => Ok(()),
+        }
+    }
+
+    fn check_offer_payment(
+        &self,
+        variant: &str,
+        swaps: &str,
+        payment_token: &SwapPaymentToken,
+    ) -> Result<(), AmburError> {
+        let path = format!("{variant}.payment_token");
+        let wusdc = self
+            .network
+            .as_ref()
+            .and_then(|network| offer_payment_token(self.registry, network).ok())
+            .map(|config| config.token.id());
+        match payment_token {
+            SwapPaymentToken::Native { denom } => Err(violation(
+                &path,
+                format!(
+                    "{swaps} must be paid with the wUSDC cw20 token, not the native token {denom}"
+                ),
+            )),
+            SwapPaymentToken::Cw20 { address } if wusdc.is_some_and(|wusdc| wusdc != address) => {
+                Err(violation(
+                    &path,
+                    format!(
+                        "{swaps} must be paid with the wUSDC cw20 token ({}), not {address}",
+                        wusdc.unwrap_or_default()
+                    ),
+                ))
+            }
+            SwapPaymentToken::Cw20 { .. } => Ok(()),
+        }
+    }
+
+    // Only registered collections can be traded
+    fn check_cw721(&self, variant: &str, fields: &Map<String, Value>) -> Result<(), AmburError> {
+        let Some(cw721) = field::<String>(fields, variant, "cw721")? else {
+            return Ok(());
+        };
+        let path = format!("{variant}.cw721");
+        let registrations: Vec<_> = lookup_address(&cw721, self.registry)
+            .into_iter()
+            .filter(|(_, role, _)| role == &ContractRole::Token)
+            .collect();
+        match (registrations.first(), &self.network) {
+            (None, _) => Err(violation(
+                &path,
+                format!(
+                    "{cw721} isn't the token contract of a registered NFT collection (see 'list_nft_collections')"
+                ),
+            )),
+            (Some((network, _, collection)), Some(expected))
+                if !registrations
+                    .iter()
+                    .any(|(network, _, _)| network == expected) =>
+            {
+                Err(violation(
+                    &path,
+                    format!(
+                        "{cw721} is the {} token contract on {network:?}, not on {expected:?}",
+                        collection.as_deref().unwrap_or_default()
+                    ),
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // Block heights can't be checked without the chain's current height
+    fn check_expires(&self, variant: &str, fields: &Map<String, Value>) -> Result<(), AmburError> {
+        match field::<Expiration>(fields, variant, "expires")? {
+            Some(Expiration::AtTime(time)) if time <= self.now => Err(violation(
+                &format!("{variant}.expires"),
+                format!(
+                    "The expiration (unix time {}) must be in the future",
+                    time.seconds()
+                ),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+// Agents tend to copy the ids of the documentation's examples
+fn check_new_id(variant: &str, fields: &Map<String, Value>) -> Result<(), AmburError> {
+    match field::<String>(fields, variant, "id")? {
+        Some(id) if EXAMPLE_SWAP_IDS.contains(&id.as_str()) => Err(violation(
+            &format!("{variant}.id"),
+            format!(
+                "{id} is the swap id of a documentation example, leave the id out to have one generated (or see 'generate_swap_id')"
+            ),
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn check_fee_percentages(path: &str, value: &Value) -> Result<(), AmburError> {
+    match value {
+        Value::Object(fields) => {
+            for (name, value) in fields {
+                let path = format!("{path}.{name}");
+                if name == "fee_percentage"
+                    && !value.is_null()
+                    && value.as_u64().is_none_or(|fee| fee > MAX_FEE_PERCENTAGE)
+                {
+                    return Err(violation(
+                        &path,
+                        format!(
+                            "fee_percentage must be a whole percentage from 0 to {MAX_FEE_PERCENTAGE}, got {value}"
+                        ),
+                    ));
+                }
+                check_fee_percentages(&path, value)?;
+            }
+            Ok(())
+        }
+        Value::Array(values) => values
+            .iter()
+            .try_for_each(|value| check_fee_percentages(path, value)),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{
+        AmburCollection, AmburContract, CollectionContract, TOKEN_ARCHIES_CONSTANTINE,
+        TOKEN_ARCHIES_MAINNET, TOKEN_DERPIES_CONSTANTINE, USDC_DENOM_MAINNET, WUSDC_MAINNET,
+    };
+    use serde_json::json;
+
+    // Archies on both networks, and Derpies only on Constantine
+    fn registry() -> Registry {
+        let collection = |name: &str, token: Vec<AmburContract>| AmburCollection {
+            name: name.to_string(),
+            description: String::new(),
+            contract_addresses: CollectionContract {
+                token,
+                minter: vec![],
+            },
+        };
+        Registry {
+            collections: vec![
+                collection(
+                    "Archies",
+                    vec![
+                        AmburContract {
+                            network: ArchwayNetwork::Mainnet,
+                            contract_address: TOKEN_ARCHIES_MAINNET.to_string(),
+                        },
+                        AmburContract {
+                            network: ArchwayNetwork::Constantine,
+                            contract_address: TOKEN_ARCHIES_CONSTANTINE.to_string(),
+                        },
+                    ],
+                ),
+                collection(
+                    "Derpies",
+                    vec![AmburContract {
+                        network: ArchwayNetwork::Constantine,
+                        contract_address: TOKEN_DERPIES_CONSTANTINE.to_string(),
+                    }],
+                ),
+            ],
+            ..Registry::default()
+        }
+    }
+
+    fn msg(value: Value) -> ExecuteMsg {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn create(swap_type: &str, payment_token: Value) -> Value {
+        json!({
+            "create": {
+                "id": "3b1f6f8e-4f4e-4d43-9a57-2a4f2b6c2a10",
+                "cw721": TOKEN_ARCHIES_MAINNET,
+                "token_id": "840",
+                "payment_token": payment_token,
+                "price": "8880000",
+                "swap_type": swap_type,
+                "expires": { "never": {} }
+            }
+        })
+    }
+
+    fn usdc() -> Value {
+        json!({ "native": { "denom": USDC_DENOM_MAINNET } })
+    }
+
+    fn wusdc() -> Value {
+        json!({ "cw20": { "address": WUSDC_MAINNET } })
+    }
+
+    fn check(value: Value) -> Result<(), AmburError> {
+        let registry = registry();
+        MarketplaceRules::new(&registry, Some(ArchwayNetwork::Mainnet)).check(&msg(value))
+    }
+
+    fn violated(value: Value, path: &str) {
+        let err = check(value).unwrap_err();
+        assert_eq!(err.code, ErrorCode::RuleViolation, "{}", err.message);
+        assert_eq!(err.path.as_deref(), Some(path), "{}", err.message);
+    }
+
+    #[test]
+    fn check_accepts_well_formed_swaps() {
+        check(create("Sale", usdc())).unwrap();
+        check(create("Offer", wusdc())).unwrap();
+    }
+
+    #[test]
+    fn check_refuses_payment_tokens_of_the_wrong_kind() {
+        violated(create("Sale", wusdc()), "create.payment_token");
+        violated(create("Offer", usdc()), "create.payment_token");
+    }
+
+    #[test]
+    fn check_refuses_another_wusdc_contract() {
+        let other = json!({ "cw20": { "address": TOKEN_ARCHIES_MAINNET } });
+        violated(create("Offer", other), "create.payment_token");
+        // The wUSDC address is only known on networks with a registered wUSDC
+        let registry = registry();
+        let other = json!({ "cw20": { "address": TOKEN_ARCHIES_MAINNET } });
+        let mut value = create("Offer", other);
+        value["create"]["cw721"] = json!(TOKEN_ARCHIES_CONSTANTINE);
+        MarketplaceRules::new(&registry, Some(ArchwayNetwork::Constantine))
+            .check(&msg(value))
+            .unwrap();
+    }
+
+    #[test]
+    fn check_refuses_unregistered_cw721s() {
+        let mut value = create("Sale", usdc());
+        value["create"]["cw721"] = json!(WUSDC_MAINNET);
+        violated(value, "create.cw721");
+    }
+
+    #[test]
+    fn check_refuses_cw721s_registered_on_another_network() {
+        let mut value = create("Sale", usdc());
+        value["create"]["cw721"] = json!(TOKEN_DERPIES_CONSTANTINE);
+        let err = check(value).unwrap_err();
+        assert_eq!(err.path.as_deref(), Some("create.cw721"));
+        assert!(err.message.contains("Derpies"), "{}", err.message);
+        assert!(err.message.contains("Constantine"), "{}", err.message);
+    }
+
+    #[test]
+    fn check_refuses_expirations_in_the_past() {
+        let mut value = create("Offer", wusdc());
+        value["create"]["expires"] = json!({ "at_time": "1000000000000000000" });
+        violated(value, "create.expires");
+        let update = json!({
+            "update": { "id": "3b1f6f8e", "price": "8880000", "expires": { "at_time": "1000000000000000000" } }
+        });
+        violated(update, "update.expires");
+        // A time in the future and block heights are fine
+        let mut value = create("Offer", wusdc());
+        value["create"]["expires"] = json!({ "at_time": "4102444800000000000" });
+        check(value).unwrap();
+        let mut value = create("Offer", wusdc());
+        value["create"]["expires"] = json!({ "at_height": 1 });
+        check(value).unwrap();
+    }
+
+    #[test]
+    fn check_refuses_documentation_example_ids() {
+        for id in EXAMPLE_SWAP_IDS {
+            let mut value = create("Sale", usdc());
+            value["create"]["id"] = json!(id);
+            violated(value, "create.id");
+        }
+    }
+
+    #[test]
+    fn check_fee_percentages_finds_nested_fees() {
+        let fees = |fee: Value| {
+            json!({
+                "royalties": [{ "recipient": "archway1recipient", "share": { "fee_percentage": fee } }]
+            })
+        };
+        check_fee_percentages("set_royalties", &fees(json!(30))).unwrap();
+        check_fee_percentages("set_royalties", &fees(json!(0))).unwrap();
+        for fee in [json!(31), json!(250), json!(2.5), json!("10"), json!(-1)] {
+            let err = check_fee_percentages("set_royalties", &fees(fee.clone())).unwrap_err();
+            assert_eq!(err.code, ErrorCode::RuleViolation, "{fee}");
+            assert_eq!(
+                err.path.as_deref(),
+                Some("set_royalties.royalties.share.fee_percentage"),
+                "{fee}"
+            );
+        }
+    }
+
+    fn offer_details(price: u128) -> SwapDetails {
+        SwapDetails {
+            creator: "archway1creator".to_string(),
+            contract: Some(TOKEN_ARCHIES_MAINNET.to_string()),
+            token_id: Some("427".to_string()),
+            price: Uint128::new(price),
+            payment_token: SwapPaymentToken::Cw20 {
+                address: WUSDC_MAINNET.to_string(),
+            },
+            swap_type: "Offer".to_string(),
+            expires: None,
+        }
+    }
+
+    #[test]
+    fn check_update_keeps_the_price_of_offers() {
+        let registry = registry();
+        let rules = MarketplaceRules::new(&registry, Some(ArchwayNetwork::Mainnet));
+        let update = |price: &str| {
+            msg(json!({
+                "update": { "id": "3b1f6f8e", "price": price, "expires": { "never": {} } }
+            }))
+        };
+        assert_eq!(
+            rules.updated_swap(&update("888888")).unwrap().as_deref(),
+            Some("3b1f6f8e")
+        );
+        rules
+            .check_update(&update("888888"), &offer_details(888_888))
+            .unwrap();
+        let err = rules
+            .check_update(&update("999999"), &offer_details(888_888))
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::RuleViolation);
+        assert_eq!(err.path.as_deref(), Some("update.price"));
+        // The price of a Sale can be updated
+        let sale = SwapDetails {
+            swap_type: "Sale".to_string(),
+            ..offer_details(888_888)
+        };
+        rules.check_update(&update("999999"), &sale).unwrap();
+    }
+}
@@ -0,0 +1,980 @@
+use cosmwasm_std::{Binary, to_json_binary};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::Path;
+
+use crate::address::ContractRole;
+use crate::error::{AmburError, ErrorCode, from_json_str};
+use crate::query::msg_variant;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MsgKind {
+    Instantiate,
+    Execute,
+    Query,
+    Migrate,
+    Sudo,
+}
+
+impl MsgKind {
+    const ALL: [MsgKind; 5] = [
+        MsgKind::Instantiate,
+        MsgKind::Execute,
+        MsgKind::Query,
+        MsgKind::Migrate,
+        MsgKind::Sudo,
+    ];
+
+    fn file_stem(&self) -> &'static str {
+        match self {
+            MsgKind::Instantiate => "instantiate",
+            MsgKind::Execute => "execute",
+            MsgKind::Query => "query",
+            MsgKind::Migrate => "migrate",
+            MsgKind::Sudo => "sudo",
+        }
+    }
+}
+
+// The JSON schemas of a contract's messages, as written by cosmwasm-schema's
+// `write_api!` (a single <contract>.json API file, or its raw/ directory)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ContractSchema {
+    pub contract_name: String,
+    #[serde(default)]
+    pub contract_version: Option<String>,
+    #[serde(default)]
+    pub instantiate: Option<Value>,
+    #[serde(default)]
+    pub execute: Option<Value>,
+    #[serde(default)]
+    pub query: Option<Value>,
+    #[serde(default)]
+    pub migrate: Option<Value>,
+    #[serde(default)]
+    pub sudo: Option<Value>,
+    // Response schemas by QueryMsg variant
+    #[serde(default)]
+    pub responses: Option<BTreeMap<String, Value>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ContractSchemaSummary {
+    pub contract_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contract_version: Option<String>,
+    pub msgs: Vec<MsgKind>,
+    // QueryMsg variants whose responses are validated
+    pub query_responses: Vec<String>,
+}
+
+fn invalid_schema(message: impl Into<String>) -> AmburError {
+    AmburError::new(ErrorCode::InvalidSchema, message)
+}
+
+fn read_json(path: &Path) -> Result<Value, AmburError> {
+    let contents = fs::read_to_string(path).map_err(|err| {
+        invalid_schema(format!(
+            "Error reading schema file {}: {err}",
+            path.display()
+        ))
+    })?;
+    serde_json::from_str(&contents).map_err(|err| {
+        invalid_schema(format!(
+            "Error parsing schema file {}: {err}",
+            path.display()
+        ))
+    })
+}
+
+fn json_files(dir: &Path) -> Result<Vec<std::path::PathBuf>, AmburError> {
+    let entries = fs::read_dir(dir).map_err(|err| {
+        invalid_schema(format!(
+            "Error reading schema directory {}: {err}",
+            dir.display()
+        ))
+    })?;
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+fn is_api_file(value: &Value) -> bool {
+    value.get("contract_name").is_some_and(Value::is_string)
+}
+
+fn parse_api_file(path: &Path, value: Value) -> Result<ContractSchema, AmburError> {
+    serde_json::from_value(value).map_err(|err| {
+        invalid_schema(format!(
+            "{} isn't a cosmwasm-schema API file: {err}",
+            path.display()
+        ))
+    })
+}
+
+// A contract's schema/ directory: its API file when there is one, otherwise
+// the per message files of raw/ (or of older cosmwasm-schema versions, e.g.
+// execute_msg.json), named after the directory
+fn load_contract_dir(dir: &Path) -> Result<ContractSchema, AmburError> {
+    for path in json_files(dir)? {
+        if path.extension().is_some_and(|ext| ext == "json") {
+            let value = read_json(&path)?;
+            if is_api_file(&value) {
+                return parse_api_file(&path, value);
+            }
+        }
+    }
+    let raw = dir.join("raw");
+    let raw = if raw.is_dir() { raw } else { dir.to_path_buf() };
+    let mut schema = ContractSchema {
+        contract_name: dir
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        contract_version: None,
+        instantiate: None,
+        execute: None,
+        query: None,
+        migrate: None,
+        sudo: None,
+        responses: None,
+    };
+    for kind in MsgKind::ALL {
+        let stem = kind.file_stem();
+        let path = [format!("{stem}.json"), format!("{stem}_msg.json")]
+            .into_iter()
+            .map(|file| raw.join(file))
+            .find(|path| path.is_file());
+        if let Some(path) = path {
+            *schema.msg_schema_mut(kind) = Some(read_json(&path)?);
+        }
+    }
+    let mut responses = BTreeMap::new();
+    for path in json_files(&raw)? {
+        let variant = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix("response_to_"))
+            .and_then(|name| name.strip_suffix(".json"));
+        if let Some(variant) = variant {
+            responses.insert(variant.to_string(), read_json(&path)?);
+        }
+    }
+    schema.responses = Some(responses).filter(|responses| !responses.is_empty());
+    if MsgKind::ALL
+        .iter()
+        .all(|kind| schema.msg_schema(*kind).is_none())
+    {
+        return Err(invalid_schema(format!(
+            "No cosmwasm-schema message schemas found in {}",
+            dir.display()
+        )));
+    }
+    Ok(schema)
+}
+
+// Every API file, or contract directory, in `dir` is a contract. Schemas are
+// checked when they're loaded, so the server refuses to start with a broken one
+pub fn load_schema_dir(dir: &Path) -> Result<Vec<ContractSchema>, AmburError> {
+    let mut schemas = vec![];
+    for path in json_files(dir)? {
+        if path.is_dir() {
+            schemas.push(load_contract_dir(&path)?);
+        } else if path.extension().is_some_and(|ext| ext == "json") {
+            let value = read_json(&path)?;
+            if !is_api_file(&value) {
+                return Err(invalid_schema(format!(
+                    "{} isn't a cosmwasm-schema API file (it has no contract_name)",
+                    path.display()
+                )));
+            }
+            schemas.push(parse_api_file(&path, value)?);
+        }
+    }
+    let mut names = HashSet::new();
+    for schema in &schemas {
+        if !names.insert(schema.contract_name.to_lowercase()) {
+            return Err(invalid_schema(format!(
+                "Contract {} has more than one schema in {}",
+                schema.contract_name,
+                dir.display()
+            )));
+        }
+        schema.check()?;
+    }
+    Ok(schemas)
+}
+
+pub fn find_schema<'a>(
+    schemas: &'a [ContractSchema],
+    contract: &str,
+) -> Result<&'a ContractSchema, AmburError> {
+    schemas
+        .iter()
+        .find(|schema| schema.contract_name.eq_ignore_ascii_case(contract.trim()))
+        .ok_or_else(|| {
+            let names: Vec<&str> = schemas
+                .iter()
+                .map(|schema| schema.contract_name.as_str())
+                .collect();
+            AmburError::new(
+                ErrorCode::UnknownContract,
+                if names.is_empty() {
+                    format!(
+                        "No contract schemas are loaded, restart the server with --schema-dir to load {contract}'s"
+                    )
+                } else {
+                    format!(
+                        "No schema is loaded for contract {contract}, expected one of {}",
+                        names.join(", ")
+                    )
+                },
+            )
+        })
+}
+
+impl ContractSchema {
+    pub fn msg_schema(&self, kind: MsgKind) -> Option<&Value> {
+        match kind {
+            MsgKind::Instantiate => self.instantiate.as_ref(),
+            MsgKind::Execute => self.execute.as_ref(),
+            MsgKind::Query => self.query.as_ref(),
+            MsgKind::Migrate => self.migrate.as_ref(),
+            MsgKind::Sudo => self.sudo.as_ref(),
+        }
+    }
+
+    fn msg_schema_mut(&mut self, kind: MsgKind) -> &mut Option<Value> {
+        match kind {
+            MsgKind::Instantiate => &mut self.instantiate,
+            MsgKind::Execute => &mut self.execute,
+            MsgKind::Query => &mut self.query,
+            MsgKind::Migrate => &mut self.migrate,
+            MsgKind::Sudo => &mut self.sudo,
+        }
+    }
+
+    pub fn required_msg_schema(&self, kind: MsgKind) -> Result<&Value, AmburError> {
+        self.msg_schema(kind).ok_or_else(|| {
+            AmburError::new(
+                ErrorCode::UnknownContract,
+                format!(
+                    "The schema of contract {} has no {kind:?}Msg",
+                    self.contract_name
+                ),
+            )
+        })
+    }
+
+    pub fn summary(&self) -> ContractSchemaSummary {
+        ContractSchemaSummary {
+            contract_name: self.contract_name.clone(),
+            contract_version: self.contract_version.clone(),
+            msgs: MsgKind::ALL
+                .into_iter()
+                .filter(|kind| self.msg_schema(*kind).is_some())
+                .collect(),
+            query_responses: self
+                .responses
+                .iter()
+                .flat_map(|responses| responses.keys().cloned())
+                .collect(),
+        }
+    }
+
+    fn check(&self) -> Result<(), AmburError> {
+        let schemas = MsgKind::ALL
+            .iter()
+            .filter_map(|kind| Some((format!("{kind:?}Msg"), self.msg_schema(*kind)?)))
+            .chain(self.responses.iter().flat_map(|responses| {
+                responses
+                    .iter()
+                    .map(|(variant, schema)| (format!("response to {variant}"), schema))
+            }));
+        for (name, schema) in schemas {
+            jsonschema::draft7::new(schema).map_err(|err| {
+                invalid_schema(format!(
+                    "The {name} schema of contract {} is invalid: {err}",
+                    self.contract_name
+                ))
+            })?;
+        }
+        Ok(())
+    }
+
+    // Validate a message against the schema of its kind. Messages of
+    // externally tagged enums are validated against their variant, so that
+    // errors point into the variant instead of at the enum's oneOf
+    pub fn validate_msg(&self, kind: MsgKind, msg: &str) -> Result<Value, AmburError> {
+        let schema = self.required_msg_schema(kind)?;
+        let value = from_json_str::<Value>(msg)?;
+        let expected_type = format!("{} {kind:?}Msg", self.contract_name);
+        let schema = match (msg_variant(&value), variants(schema)) {
+            (Some(variant), Some(variants)) => {
+                match variants.iter().find(|(name, _)| name == &variant) {
+                    Some((_, branch)) => with_definitions(schema, branch),
+                    None => return Err(unknown_variant(&variant, &variants, expected_type)),
+                }
+            }
+            _ => schema.clone(),
+        };
+        validate(&schema, &value, expected_type)?;
+        Ok(value)
+    }
+
+    // validate_msg, serialized for a WasmQuery or WasmMsg
+    pub fn msg_binary(&self, kind: MsgKind, msg: &str) -> Result<Binary, AmburError> {
+        let value = self.validate_msg(kind, msg)?;
+        to_json_binary(&value).map_err(|err| AmburError::serialization::<Binary>(err.to_string()))
+    }
+
+    // Validate a query response against the response schema of the query's
+    // variant; returns the response's type when there's a schema for it
+    pub fn validate_response(
+        &self,
+        query_msg: &str,
+        data: &Value,
+    ) -> Result<Option<String>, AmburError> {
+        let Some(variant) = serde_json::from_str::<Value>(query_msg)
+            .ok()
+            .and_then(|msg| msg_variant(&msg))
+        else {
+            return Ok(None);
+        };
+        let Some(schema) = self
+            .responses
+            .as_ref()
+            .and_then(|responses| responses.get(&variant))
+        else {
+            return Ok(None);
+        };
+        let expected_type = schema["title"]
+            .as_str()
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("response to {variant}"));
+        validate(
+            schema,
+            data,
+            format!("{} {expected_type}", self.contract_name),
+        )?;
+        Ok(Some(expected_type))
+    }
+}
+
+// The variants of an enum message schema, by name: {"required": ["name"]} for
+// struct and tuple variants, {"enum": ["name"]} for unit variants
+fn variants(schema: &Value) -> Option<Vec<(String, Value)>> {
+    let mut variants = vec![];
+    for branch in schema["oneOf"].as_array()? {
+        let names = branch["required"]
+            .as_array()
+            .filter(|_| branch["type"] == "object")
+            .or_else(|| branch["enum"].as_array())?;
+        for name in names.iter().filter_map(Value::as_str) {
+            variants.push((name.to_string(), branch.clone()));
+        }
+    }
+    Some(variants)
+}
+
+fn unknown_variant(
+    variant: &str,
+    variants: &[(String, Value)],
+    expected_type: String,
+) -> AmburError {
+    let names: Vec<String> = variants
+        .iter()
+        .map(|(name, _)| format!("`{name}`"))
+        .collect();
+    AmburError {
+        expected_type: Some(expected_type),
+        ..AmburError::new(
+            ErrorCode::InvalidMessage,
+            format!(
+                "unknown variant `{variant}`, expected one of {}",
+                names.join(", ")
+            ),
+        )
+    }
+}
+
+// A branch of a schema, with the root's definitions its $refs point to
+fn with_definitions(root: &Value, branch: &Value) -> Value {
+    let mut schema = match branch {
+        Value::Object(branch) => branch.clone(),
+        _ => Map::new(),
+    };
+    for key in ["definitions", "$defs"] {
+        if let Some(definitions) = root.get(key) {
+            schema.insert(key.to_string(), definitions.clone());
+        }
+    }
+    Value::Object(schema)
+}
+
+// JSON pointers (/transfer_nft/recipient) are reported like serde paths
+// (transfer_nft.recipient)
+fn instance_path(pointer: &str) -> Option<String> {
+    let mut path = String::new();
+    for segment in pointer.split('/').skip(1) {
+        if segment.parse::<usize>().is_ok() {
+            path.push_str(&format!("[{segment}]"));
+        } else {
+            if !path.is_empty() {
+                path.push('.');
+            }
+            path.push_str(&segment.replace("~1", "/").replace("~0", "~"));
+        }
+    }
+    Some(path).filter(|path| !path.is_empty())
+}
+
+fn validate(schema: &Value, value: &Value, expected_type: String) -> Result<(), AmburError> {
+    let validator = jsonschema::draft7::new(schema)
+        .map_err(|err| invalid_schema(format!("The {expected_type} schema is invalid: {err}")))?;
+    match validator.iter_errors(value).next() {
+        None => Ok(()),
+        Some(err) => Err(AmburError {
+            expected_type: Some(expected_type),
+            path: instance_path(&err.instance_path.to_string()),
+            ..AmburError::new(ErrorCode::InvalidMessage, err.to_string())
+        }),
+    }
+}
+
+// One variant of an enum message schema, with every definition it references
+// inlined, and a minimal message that passes the schema
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VariantSchema {
+    pub variant: String,
+    pub schema: Value,
+    // None when no valid message could be generated from the schema alone
+    pub example: Option<Value>,
+}
+
+// A single entry point of an Ambur marketplace, cw721 token, minter or cw20 contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EntryPointDescription {
+    pub contract_kind: ContractRole,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nft: Option<String>,
+    pub msg_type: MsgKind,
+    #[serde(flatten)]
+    pub entry_point: VariantSchema,
+}
+
+// Whether the enum message schema `root` has a `variant` entry point
+pub fn has_variant(root: &Value, variant: &str) -> bool {
+    variants(root).is_some_and(|variants| variants.iter().any(|(name, _)| name == variant))
+}
+
+pub fn describe_variant(
+    root: &Value,
+    variant: &str,
+    expected_type: String,
+) -> Result<VariantSchema, AmburError> {
+    let variants = variants(root).ok_or_else(|| {
+        AmburError::new(
+            ErrorCode::InvalidSchema,
+            format!("The {expected_type} schema isn't an enum of entry points"),
+        )
+    })?;
+    let mut branch = match variants.iter().find(|(name, _)| name == variant) {
+        Some((_, branch)) => branch.clone(),
+        None => return Err(unknown_variant(variant, &variants, expected_type)),
+    };
+    // Unit variants share a single {"enum": [...]} branch
+    if branch.get("enum").is_some() {
+        branch["enum"] = Value::Array(vec![Value::String(variant.to_string())]);
+    }
+    let definitions = definitions(root);
+    let example = example(&branch, &definitions, 0).filter(|example| {
+        validate(
+            &with_definitions(root, &branch),
+            example,
+            expected_type.clone(),
+        )
+        .is_ok()
+    });
+    Ok(VariantSchema {
+        variant: variant.to_string(),
+        schema: inline_definitions(&branch, &definitions),
+        example,
+    })
+}
+
+fn definitions(root: &Value) -> Map<String, Value> {
+    let mut definitions = Map::new();
+    for key in ["definitions", "$defs"] {
+        if let Some(Value::Object(defs)) = root.get(key) {
+            definitions.extend(defs.clone());
+        }
+    }
+    definitions
+}
+
+fn ref_name(value: &Value) -> Option<&str> {
+    let reference = value.get("$ref")?.as_str()?;
+    reference
+        .strip_prefix("#/definitions/")
+        .or_else(|| reference.strip_prefix("#/$defs/"))
+}
+
+// Replace each $ref with the definition it points to. Recursive types can't be
+// inlined, their $refs are kept and their definitions carried along instead
+fn inline_definitions(branch: &Value, definitions: &Map<String, Value>) -> Value {
+    let mut recursive = BTreeSet::new();
+    let mut schema = inline(branch, definitions, &mut vec![], &mut recursive);
+    let mut carried = Map::new();
+    let mut pending: Vec<String> = recursive.into_iter().collect();
+    while let Some(name) = pending.pop() {
+        if carried.contains_key(&name) {
+            continue;
+        }
+        let mut nested = BTreeSet::new();
+        let definition = definitions.get(&name).cloned().unwrap_or_default();
+        let definition = inline(
+            &definition,
+            definitions,
+            &mut vec![name.clone()],
+            &mut nested,
+        );
+        carried.insert(name, definition);
+        pending.extend(nested);
+    }
+    if !carried.is_empty()
+        && let Value::Object(schema) = &mut schema
+    {
+        schema.insert("definitions".to_string(), Value::Object(carried));
+    }
+    schema
+}
+
+fn inline(
+    value: &Value,
+    definitions: &Map<String, Value>,
+    stack: &mut Vec<String>,
+    recursive: &mut BTreeSet<String>,
+) -> Value {
+    match value {
+        Value::Object(object) => {
+            if let Some(name) = ref_name(value)
+                && let Some(definition) = definitions.get(name)
+            {
+                if stack.iter().any(|seen| seen == name) {
+                    recursive.insert(name.to_string());
+                    return value.clone();
+                }
+                stack.push(name.to_string());
+                let mut inlined = match inline(definition, definitions, stack, recursive) {
+                    Value::Object(inlined) => inlined,
+                    _ => Map::new(),
+                };
+                stack.pop();
+                // Keywords next to the $ref (e.g. a field's description) win
+                for (key, value) in object.iter().filter(|(key, _)| *key != "$ref") {
+                    inlined.insert(key.clone(), inline(value, definitions, stack, recursive));
+                }
+                return Value::Object(inlined);
+            }
+            Value::Object(
+                object
+                    .iter()
+                    .map(|(key, value)| (key.clone(), inline(value, definitions, stack, recursive)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| inline(item, definitions, stack, recursive))
+                .collect(),
+        ),
+        value => value.clone(),
+    }
+}
+
+// cosmwasm-std's integers and decimals are strings holding a number
+const NUMERIC_STRINGS: [&str; 10] = [
+    "Uint64",
+    "Uint128",
+    "Uint256",
+    "Uint512",
+    "Int64",
+    "Int128",
+    "Int256",
+    "Int512",
+    "Decimal",
+    "Decimal256",
+];
+
+// The smallest value that satisfies `schema`: required fields only, the first
+// alternative of a oneOf, and zero, false or empty strings for scalars
+fn example(schema: &Value, definitions: &Map<String, Value>, depth: usize) -> Option<Value> {
+    if depth > 32 {
+        return None;
+    }
+    if let Some(name) = ref_name(schema) {
+        if NUMERIC_STRINGS.contains(&name) {
+            return Some(Value::String("0".to_string()));
+        }
+        return example(definitions.get(name)?, definitions, depth + 1);
+    }
+    if let Some(value) = schema.get("const") {
+        return Some(value.clone());
+    }
+    if let Some(values) = schema["enum"].as_array() {
+        return values.first().cloned();
+    }
+    for key in ["allOf", "oneOf", "anyOf"] {
+        if let Some(branches) = schema[key].as_array() {
+            return branches
+                .iter()
+                .find_map(|branch| example(branch, definitions, depth + 1));
+        }
+    }
+    let types: Vec<&str> = match &schema["type"] {
+        Value::String(kind) => vec![kind.as_str()],
+        Value::Array(kinds) => kinds.iter().filter_map(Value::as_str).collect(),
+        _ => vec![],
+    };
+    if types.contains(&"null") {
+        return Some(Value::Null);
+    }
+    let minimum = schema["minimum"].as_f64().unwrap_or(0.0).ceil();
+    match types.first().copied() {
+        Some("object") => {
+            let mut object = Map::new();
+            for field in schema["required"].as_array().into_iter().flatten() {
+                let field = field.as_str()?;
+                let field_schema = schema["properties"].get(field)?;
+                object.insert(
+                    field.to_string(),
+                    example(field_schema, definitions, depth + 1)?,
+                );
+            }
+            Some(Value::Object(object))
+        }
+        Some("array") => {
+            let min_items = schema["minItems"].as_u64().unwrap_or(0) as usize;
+            let items = match &schema["items"] {
+                Value::Array(items) => items
+                    .iter()
+                    .map(|item| example(item, definitions, depth + 1))
+                    .collect::<Option<Vec<Value>>>()?,
+                Value::Object(_) if min_items > 0 => {
+                    vec![example(&schema["items"], definitions, depth + 1)?; min_items]
+                }
+                _ => vec![],
+            };
+            Some(Value::Array(items))
+        }
+        Some("string") => Some(Value::String(String::new())),
+        Some("integer") => Some(Value::from(minimum as i64)),
+        Some("number") => Some(Value::from(minimum)),
+        Some("boolean") => Some(Value::Bool(false)),
+        _ => Some(Value::Object(Map::new())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use philabs_cw721_marketplace::msg::DetailsResponse;
+    use philabs_cw721_marketplace::msg::{ExecuteMsg, QueryMsg};
+    use schemars::schema_for;
+    use serde::de::DeserializeOwned;
+    use serde_json::json;
+    use std::path::PathBuf;
+
+    // A schema directory of its own in the temp dir, removed when dropped
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> Self {
+            let dir = std::env::temp_dir().join(format!("ambur-schemas-{}", uuid::Uuid::new_v4()));
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        fn write(&self, file: &str, value: &Value) {
+            let path = self.0.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, value.to_string()).unwrap();
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    // The minimal value of a (non enum) schema
+    fn example_value(root: &Value) -> Value {
+        example(root, &definitions(root), 0).unwrap()
+    }
+
+    fn marketplace_api() -> Value {
+        json!({
+            "contract_name": "marketplace",
+            "contract_version": "2.1.1",
+            "execute": root::<ExecuteMsg>(),
+            "query": root::<QueryMsg>(),
+            "responses": { "details": root::<DetailsResponse>() }
+        })
+    }
+
+    fn root<T: JsonSchema>() -> Value {
+        serde_json::to_value(schema_for!(T)).unwrap()
+    }
+
+    fn refs(value: &Value, found: &mut Vec<String>) {
+        match value {
+            Value::Object(object) => {
+                if let Some(name) = ref_name(value) {
+                    found.push(name.to_string());
+                }
+                object.values().for_each(|value| refs(value, found));
+            }
+            Value::Array(items) => items.iter().for_each(|item| refs(item, found)),
+            _ => {}
+        }
+    }
+
+    // Every variant of a message type is described with a self contained schema,
+    // and an example that passes it and deserializes into the message type
+    fn check_every_variant<T: JsonSchema + DeserializeOwned>() {
+        let root = root::<T>();
+        let names: Vec<String> = variants(&root)
+            .unwrap()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert!(!names.is_empty());
+        for name in names {
+            let described = describe_variant(&root, &name, "msg".to_string()).unwrap();
+            assert_eq!(described.variant, name);
+
+            // Any $ref left is a recursive definition, carried along with the schema
+            let mut found = vec![];
+            refs(&described.schema, &mut found);
+            for reference in found {
+                assert!(
+                    described.schema["definitions"].get(&reference).is_some(),
+                    "{name}: dangling $ref {reference}"
+                );
+            }
+
+            let example = described
+                .example
+                .unwrap_or_else(|| panic!("{name}: no example"));
+            assert_eq!(msg_variant(&example).as_deref(), Some(name.as_str()));
+            validate(&described.schema, &example, name.clone()).unwrap();
+            serde_json::from_value::<T>(example.clone()).unwrap_or_else(|err| {
+                panic!("{name}: example {example} doesn't deserialize: {err}")
+            });
+        }
+    }
+
+    #[test]
+    fn describes_every_marketplace_execute_msg() {
+        check_every_variant::<ExecuteMsg>();
+    }
+
+    #[test]
+    fn describes_every_marketplace_query_msg() {
+        check_every_variant::<QueryMsg>();
+    }
+
+    #[test]
+    fn refuses_unknown_variants() {
+        let root = root::<ExecuteMsg>();
+        let err = describe_variant(&root, "nope", "ExecuteMsg".to_string()).unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidMessage);
+        assert_eq!(err.expected_type.as_deref(), Some("ExecuteMsg"));
+        for (name, _) in variants(&root).unwrap() {
+            assert!(err.message.contains(&format!("`{name}`")));
+        }
+        let err = describe_variant(&json!({ "type": "string" }), "nope", "String".to_string())
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidSchema);
+    }
+
+    #[test]
+    fn describes_unit_variants_and_recursive_definitions() {
+        let root = json!({
+            "oneOf": [
+                { "type": "string", "enum": ["pause", "resume"] },
+                {
+                    "type": "object",
+                    "required": ["tree"],
+                    "properties": { "tree": { "$ref": "#/definitions/Node" } }
+                }
+            ],
+            "definitions": {
+                "Node": {
+                    "type": "object",
+                    "required": ["name"],
+                    "properties": {
+                        "name": { "type": "string" },
+                        "children": { "type": "array", "items": { "$ref": "#/definitions/Node" } }
+                    }
+                }
+            }
+        });
+        let resume = describe_variant(&root, "resume", "Msg".to_string()).unwrap();
+        assert_eq!(resume.schema["enum"], json!(["resume"]));
+        assert_eq!(resume.example, Some(json!("resume")));
+
+        let tree = describe_variant(&root, "tree", "Msg".to_string()).unwrap();
+        assert_eq!(tree.example, Some(json!({ "tree": { "name": "" } })));
+        assert_eq!(
+            tree.schema["properties"]["tree"]["required"],
+            json!(["name"])
+        );
+        assert!(tree.schema["definitions"]["Node"].is_object());
+    }
+
+    #[test]
+    fn leaves_out_examples_that_fail_the_schema() {
+        let root = json!({
+            "oneOf": [{
+                "type": "object",
+                "required": ["set"],
+                "properties": {
+                    "set": {
+                        "type": "object",
+                        "required": ["code"],
+                        "properties": { "code": { "type": "string", "pattern": "^[A-Z]{3}$" } }
+                    }
+                }
+            }]
+        });
+        let described = describe_variant(&root, "set", "Msg".to_string()).unwrap();
+        assert_eq!(described.example, None);
+    }
+
+    #[test]
+    fn loads_api_files_and_raw_directories() {
+        let dir = TempDir::new();
+        dir.write("marketplace.json", &marketplace_api());
+        dir.write(
+            "counter/raw/execute.json",
+            &json!({
+                "oneOf": [{
+                    "type": "object",
+                    "required": ["increment"],
+                    "properties": { "increment": { "type": "object" } }
+                }]
+            }),
+        );
+        dir.write(
+            "counter/raw/response_to_count.json",
+            &json!({ "title": "CountResponse", "type": "object", "required": ["count"] }),
+        );
+        let schemas = load_schema_dir(&dir.0).unwrap();
+        let names: Vec<&str> = schemas.iter().map(|s| s.contract_name.as_str()).collect();
+        assert_eq!(names, ["counter", "marketplace"]);
+
+        let counter = find_schema(&schemas, "Counter").unwrap().summary();
+        assert_eq!(counter.msgs, [MsgKind::Execute]);
+        assert_eq!(counter.query_responses, ["count"]);
+        let marketplace = find_schema(&schemas, "marketplace").unwrap().summary();
+        assert_eq!(marketplace.contract_version.as_deref(), Some("2.1.1"));
+        assert_eq!(marketplace.msgs, [MsgKind::Execute, MsgKind::Query]);
+        assert_eq!(
+            find_schema(&schemas, "nope").unwrap_err().code,
+            ErrorCode::UnknownContract
+        );
+    }
+
+    #[test]
+    fn refuses_invalid_schemas_at_load() {
+        let dir = TempDir::new();
+        dir.write(
+            "broken.json",
+            &json!({ "contract_name": "broken", "execute": { "type": 5 } }),
+        );
+        let err = load_schema_dir(&dir.0).unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidSchema);
+        assert!(
+            err.message
+                .contains("ExecuteMsg schema of contract broken is invalid")
+        );
+
+        let dir = TempDir::new();
+        dir.write("plain.json", &json!({ "type": "object" }));
+        assert!(load_schema_dir(&dir.0).is_err());
+
+        let dir = TempDir::new();
+        dir.write("a.json", &marketplace_api());
+        dir.write("b.json", &marketplace_api());
+        let err = load_schema_dir(&dir.0).unwrap_err();
+        assert!(err.message.contains("more than one schema"));
+
+        let dir = TempDir::new();
+        dir.write("empty/raw/readme.json", &json!({}));
+        assert!(load_schema_dir(&dir.0).is_err());
+    }
+
+    #[test]
+    fn validates_messages_against_the_loaded_schema() {
+        let schema: ContractSchema = serde_json::from_value(marketplace_api()).unwrap();
+        let details = r#"{"details":{"id":"1"}}"#;
+        assert!(schema.validate_msg(MsgKind::Query, details).is_ok());
+
+        // Errors point into the variant
+        let err = schema
+            .validate_msg(MsgKind::Query, r#"{"details":{"id":5}}"#)
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidMessage);
+        assert_eq!(err.path.as_deref(), Some("details.id"));
+        assert_eq!(err.expected_type.as_deref(), Some("marketplace QueryMsg"));
+
+        let err = schema
+            .validate_msg(MsgKind::Query, r#"{"nope":{}}"#)
+            .unwrap_err();
+        assert!(err.message.contains("unknown variant `nope`"));
+        assert!(schema.validate_msg(MsgKind::Query, "{").is_err());
+        assert_eq!(
+            schema
+                .validate_msg(MsgKind::Migrate, "{}")
+                .unwrap_err()
+                .code,
+            ErrorCode::UnknownContract
+        );
+    }
+
+    #[test]
+    fn validates_query_responses_with_a_schema() {
+        let schema: ContractSchema = serde_json::from_value(marketplace_api()).unwrap();
+        let details = r#"{"details":{"id":"1"}}"#;
+        let response = example_value(&root::<DetailsResponse>());
+        assert_eq!(
+            schema
+                .validate_response(details, &response)
+                .unwrap()
+                .as_deref(),
+            Some("DetailsResponse")
+        );
+        assert!(
+            schema
+                .validate_response(details, &json!({ "price": 5 }))
+                .is_err()
+        );
+        // Queries without a response schema pass through
+        assert_eq!(
+            schema
+                .validate_response(r#"{"list":{}}"#, &json!(null))
+                .unwrap(),
+            None
+        );
+    }
+}
@@ -1,225 +1,1807 @@
-use cosmwasm_std::{Coin, CosmosMsg, QueryRequest, Uint128, WasmMsg, WasmQuery, to_json_binary};
+use chrono::Utc;
+use cosmwasm_std::{
+    Binary, Coin, CosmosMsg, QueryRequest, Uint128, WasmMsg, WasmQuery, to_json_binary,
+};
+use cw_utils::Expiration;
+use cw20::{Cw20ExecuteMsg, Cw20QueryMsg};
 use philabs_cw721_marketplace::msg::{ExecuteMsg, QueryMsg};
 use rmcp::{
-    Error, ServerHandler, model::CallToolResult, model::Content, model::Implementation,
-    model::ProtocolVersion, model::ServerCapabilities, model::ServerInfo, tool,
+    Error, RoleServer, ServerHandler, handler::server::tool::ToolCallContext,
+    model::CallToolRequestParam, model::CallToolResult, model::Content,
+    model::GetPromptRequestParam, model::GetPromptResult, model::Implementation,
+    model::ListPromptsResult, model::ListResourceTemplatesResult, model::ListResourcesResult,
+    model::ListToolsResult, model::PaginatedRequestParam, model::ProtocolVersion,
+    model::ReadResourceRequestParam, model::ReadResourceResult, model::ResourceContents,
+    model::ServerCapabilities, model::ServerInfo, service::RequestContext, tool,
 };
 use schemars::{JsonSchema, schema_for};
 use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
 use std::str::FromStr;
+#[cfg(feature = "signing")]
+use std::sync::Arc;
 
-use crate::contract::*;
+use crate::address::{
+    ContractMatch, ContractRole, lookup_address, lookup_address_on, resolve_address,
+    resolve_any_address,
+};
+use crate::error::{AmburError, ErrorCode, from_json_str, from_json_value};
 use crate::execute::*;
+use crate::expiration::{
+    BuiltExpiration, built_expiration, normalize_expires, parse_expiration_input, rfc3339,
+    to_datetime, to_timestamp,
+};
+use crate::family::{CollectionName, ContractCollectionName, contract_family};
 use crate::instruction::*;
-use crate::network::*;
-use crate::query::{AllResponse as AllQueryResponse, ValidatedQuery};
-use crate::server::minter::*;
-use crate::server::token::*;
+use crate::lcd::{LcdClient, LcdErrorBody, lcd_error_body};
+use crate::network::{ArchwayNetwork, NetworkConfig};
+use crate::payment::{
+    ParsedAmount, SwapPaymentToken, normalize_price, offer_payment_token, parse_funds,
+    parse_payment_amount, sale_payment_token,
+};
+use crate::prompt::{get_prompt, list_prompts};
+use crate::query::{
+    AllResponse as AllQueryResponse, DecodedResponse, QueryResult, ValidatedQuery,
+    decode_marketplace_response, decode_query, parse_raw_response, query_binary, response_notes,
+};
+use crate::registry::Registry;
+use crate::resource::{RESOURCE_MIME_TYPE, list_resource_templates, list_resources, read_resource};
+use crate::rules::MarketplaceRules;
+use crate::schema::{
+    ContractSchema, ContractSchemaSummary, EntryPointDescription, MsgKind, describe_variant,
+    find_schema, has_variant,
+};
+#[cfg(feature = "signing")]
+use crate::signer::{SignedTx, Signer};
+use crate::tx::{EncodedSignDoc, SignDocParams, SignDocRequest, build_sign_doc, build_tx_body};
+use crate::workflow::{
+    CollectionOfferRequest, GeneratedSwapId, PreparedTx, SwapDetails, approve_msg,
+    collection_offer_total, create_collection_offer_msg, create_swap_msg, describe_expiration,
+    describe_payment, deterministic_swap_id, fill_swap_id, finish_swap_msg, increase_allowance_msg,
+    new_swap_id, parse_price,
+};
+
+// Only listed and callable when a signing key is loaded
+static SIGN_TX_TOOL: &str = "sign_tx";
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct AmburMcp {
-    contracts: [AmburContract; 2],
-    collections: Vec<AmburCollection>,
+    registry: Registry,
+    schemas: Vec<ContractSchema>,
+    live_queries: bool,
+    #[cfg(feature = "signing")]
+    #[serde(skip)]
+    #[schemars(skip)]
+    signer: Option<Arc<Signer>>,
 }
 #[tool(tool_box)]
 impl AmburMcp {
     pub fn new() -> Self {
+        Self::with_registry(Registry::default())
+    }
+
+    pub fn with_registry(registry: Registry) -> Self {
         Self {
-            contracts: [
-                AmburContract {
-                    network: ArchwayNetwork::Mainnet,
-                    contract_address: CONTRACT_MAINNET.to_string(),
-                },
-                AmburContract {
-                    network: ArchwayNetwork::Constantine,
-                    contract_address: CONTRACT_CONSTANTINE.to_string(),
-                },
-            ],
-            collections: vec![
-                AmburCollection {
-                    name: "Archies".to_string(),
-                    description: ARCHIES_DESCR.to_string(),
-                    contract_addresses: CollectionContract {
-                        token: [
-                            AmburContract {
-                                network: ArchwayNetwork::Mainnet,
-                                contract_address: TOKEN_ARCHIES_MAINNET.to_string(),
-                            },
-                            AmburContract {
-                                network: ArchwayNetwork::Constantine,
-                                contract_address: TOKEN_ARCHIES_CONSTANTINE.to_string(),
-                            },
-                        ],
-                        minter: [
-                            AmburContract {
-                                network: ArchwayNetwork::Mainnet,
-                                contract_address: MINTER_ARCHIES_MAINNET.to_string(),
-                            },
-                            AmburContract {
-                                network: ArchwayNetwork::Constantine,
-                                contract_address: MINTER_ARCHIES_CONSTANTINE.to_string(),
-                            },
-                        ],
-                    },
-                },
-                AmburCollection {
-                    name: "The Foresight Ticket".to_string(),
-                    description: FORESIGHT_DESCR.to_string(),
-                    contract_addresses: CollectionContract {
-                        token: [
-                            AmburContract {
-                                network: ArchwayNetwork::Mainnet,
-                                contract_address: TOKEN_FORESIGHT_MAINNET.to_string(),
-                            },
-                            AmburContract {
-                                network: ArchwayNetwork::Constantine,
-                                contract_address: TOKEN_FORESIGHT_CONSTANTINE.to_string(),
-                            },
-                        ],
-                        minter: [
-                            AmburContract {
-                                network: ArchwayNetwork::Mainnet,
-                                contract_address: MINTER_FORESIGHT_MAINNET.to_string(),
-                            },
-                            AmburContract {
-                                network: ArchwayNetwork::Constantine,
-                                contract_address: MINTER_FORESIGHT_CONSTANTINE.to_string(),
-                            },
-                        ],
-                    },
-                },
-                AmburCollection {
-                    name: "Derpies".to_string(),
-                    description: DERPIES_DESCR.to_string(),
-                    contract_addresses: CollectionContract {
-                        token: [
-                            AmburContract {
-                                network: ArchwayNetwork::Mainnet,
-                                contract_address: TOKEN_DERPIES_MAINNET.to_string(),
-                            },
-                            AmburContract {
-                                network: ArchwayNetwork::Constantine,
-                                contract_address: TOKEN_DERPIES_CONSTANTINE.to_string(),
-                            },
-                        ],
-                        minter: [
-                            AmburContract {
-                                network: ArchwayNetwork::Mainnet,
-                                contract_address: MINTER_DERPIES_MAINNET.to_string(),
-                            },
-                            AmburContract {
-                                network: ArchwayNetwork::Constantine,
-                                contract_address: MINTER_DERPIES_CONSTANTINE.to_string(),
-                            },
-                        ],
-                    },
-                },
-                AmburCollection {
-                    name: "Ghouls".to_string(),
-                    description: GHOULS_DESCR.to_string(),
-                    contract_addresses: CollectionContract {
-                        token: [
-                            AmburContract {
-                                network: ArchwayNetwork::Mainnet,
-                                contract_address: TOKEN_GHOULS_MAINNET.to_string(),
-                            },
-                            AmburContract {
-                                network: ArchwayNetwork::Constantine,
-                                contract_address: TOKEN_GHOULS_CONSTANTINE.to_string(),
-                            },
-                        ],
-                        minter: [
-                            AmburContract {
-                                network: ArchwayNetwork::Mainnet,
-                                contract_address: MINTER_GHOULS_MAINNET.to_string(),
-                            },
-                            AmburContract {
-                                network: ArchwayNetwork::Constantine,
-                                contract_address: MINTER_GHOULS_CONSTANTINE.to_string(),
-                            },
-                        ],
-                    },
-                },
-            ],
+            registry,
+            schemas: vec![],
+            live_queries: false,
+            #[cfg(feature = "signing")]
+            signer: None,
+        }
+    }
+
+    pub fn with_schemas(mut self, schemas: Vec<ContractSchema>) -> Self {
+        self.schemas = schemas;
+        self
+    }
+
+    pub fn with_live_queries(mut self, enabled: bool) -> Self {
+        self.live_queries = enabled;
+        self
+    }
+
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    #[cfg(feature = "signing")]
+    pub fn with_signer(mut self, signer: Signer) -> Self {
+        self.signer = Some(Arc::new(signer));
+        self
+    }
+
+    fn resolve_contract(
+        &self,
+        contract_addr: &str,
+        role: ContractRole,
+        nft: Option<&str>,
+        network: Option<&ArchwayNetwork>,
+    ) -> Result<ContractMatch, AmburError> {
+        resolve_address(contract_addr, role, nft, network, &self.registry)
+    }
+
+    // The payment token comes from the amount's symbol, or from `token` (a symbol,
+    // denom or cw20 address) when the amount doesn't have one
+    fn parse_amount_in(
+        &self,
+        amount: &str,
+        token: Option<&str>,
+        network: Option<&ArchwayNetwork>,
+    ) -> Result<ParsedAmount, AmburError> {
+        let expected = token
+            .map(|token| self.registry.payment_token(token, network))
+            .transpose()?;
+        let (base_units, config) = parse_payment_amount(
+            &self.registry,
+            amount,
+            network,
+            expected.map(|config| &config.token),
+            true,
+        )?;
+        let config = config.or(expected).ok_or_else(|| {
+            AmburError::new(
+                ErrorCode::UnknownPaymentToken,
+                format!(
+                    "The payment token of {amount} isn't known, add its symbol (e.g. \"8.88 USDC\") or the 'token'"
+                ),
+            )
+        })?;
+        Ok(ParsedAmount::new(base_units, config))
+    }
+
+    fn format_amount_in(
+        &self,
+        amount: &str,
+        token: &str,
+        network: Option<&ArchwayNetwork>,
+    ) -> Result<ParsedAmount, AmburError> {
+        let base_units =
+            Uint128::from_str(amount).map_err(|_| AmburError::invalid_amount(amount))?;
+        let config = self.registry.payment_token(token, network)?;
+        Ok(ParsedAmount::new(base_units, config))
+    }
+
+    // Live queries are sent to the LCD of the contract's registered network, or of
+    // `network` when the contract isn't in the registry
+    pub async fn run_query(
+        &self,
+        contract: &ContractMatch,
+        network: Option<ArchwayNetwork>,
+        msg: &Binary,
+    ) -> Result<Value, AmburError> {
+        if !self.live_queries {
+            return Err(AmburError::new(
+                ErrorCode::QueriesDisabled,
+                "Live queries are disabled, restart the server with --live-queries to enable them",
+            ));
+        }
+        let network = match (&contract.network, network) {
+            (Some(registered), Some(requested)) if registered != &requested => {
+                return Err(AmburError::new(
+                    ErrorCode::AddressMismatch,
+                    format!(
+                        "Address {} is registered on {registered:?}, not {requested:?}",
+                        contract.contract_address
+                    ),
+                ));
+            }
+            (Some(registered), _) => registered.clone(),
+            (None, Some(requested)) => requested,
+            (None, None) => {
+                return Err(AmburError::new(
+                    ErrorCode::InvalidNetwork,
+                    format!(
+                        "The network of address {} isn't known, specify the network to query",
+                        contract.contract_address
+                    ),
+                ));
+            }
+        };
+        self.lcd(&network)?
+            .smart_query(&contract.contract_address, msg)
+            .await
+    }
+
+    // Resolve the contract and wrap `query_msg`, validated against the contract's
+    // QueryMsg, in a wasm smart QueryRequest
+    pub fn query_request(
+        &self,
+        role: ContractRole,
+        nft: Option<&str>,
+        contract_addr: String,
+        query_msg: &str,
+    ) -> Result<(QueryRequest, ContractMatch), AmburError> {
+        let contract = self.resolve_contract(&contract_addr, role, nft, None)?;
+        let msg = query_binary(&self.schemas, role, nft, query_msg)?;
+        let query_request = QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg });
+        Ok((query_request, contract))
+    }
+
+    // Run a validated query against a live network, decoding the response with
+    // the return type of the query's variant
+    pub async fn query(
+        &self,
+        role: ContractRole,
+        nft: Option<&str>,
+        contract_addr: String,
+        query_msg: String,
+        network: Option<ArchwayNetwork>,
+    ) -> Result<QueryResult, AmburError> {
+        let contract = self.resolve_contract(&contract_addr, role, nft, network.as_ref())?;
+        let msg = query_binary(&self.schemas, role, nft, &query_msg)?;
+        let data = self.run_query(&contract, network, &msg).await?;
+        let (response_type, response) = decode_query(role, &query_msg, data)?;
+        Ok(QueryResult {
+            query_msg,
+            contract,
+            notes: response_notes(response_type.as_deref()),
+            response_type,
+            response,
+        })
+    }
+
+    fn lcd(&self, network: &ArchwayNetwork) -> Result<LcdClient, AmburError> {
+        let lcd = self
+            .registry
+            .network(network)
+            .and_then(|config| config.lcd.as_deref())
+            .ok_or_else(|| {
+                AmburError::new(
+                    ErrorCode::InvalidNetwork,
+                    format!("No LCD endpoint is configured for {network:?}"),
+                )
+            })?;
+        LcdClient::new(lcd)
+    }
+
+    // A workflow's human expiration, swaps never expire when it's omitted
+    async fn expires(
+        &self,
+        expires: Option<String>,
+        network: &ArchwayNetwork,
+    ) -> Result<Expiration, AmburError> {
+        match expires {
+            Some(input) => Ok(self.expiration(&input, Some(network)).await?.expires),
+            None => Ok(Expiration::Never {}),
+        }
+    }
+
+    // Height based expirations are resolved and estimated from the chain's
+    // current height when live queries are enabled and the network is known
+    async fn expiration(
+        &self,
+        input: &str,
+        network: Option<&ArchwayNetwork>,
+    ) -> Result<BuiltExpiration, AmburError> {
+        let now = Utc::now();
+        let parsed = parse_expiration_input(input, now)?;
+        let current_height = match network {
+            Some(network) if self.live_queries && parsed.is_height_based() => {
+                Some(self.lcd(network)?.latest_block_height().await?)
+            }
+            _ => None,
+        };
+        let block_time_ms = network
+            .and_then(|network| self.registry.network(network))
+            .and_then(|config| config.block_time_ms);
+        built_expiration(
+            parsed.resolve(current_height)?,
+            now,
+            current_height,
+            block_time_ms,
+        )
+    }
+
+    // Resolve the contract and wrap `execute_msg`, validated against the contract's
+    // ExecuteMsg, in a wasm execute CosmosMsg. Marketplace messages are prepared
+    // (see prepare_marketplace_msg) first, and human payments are converted to
+    // base units with the contract network's tokens. Returns the warnings of
+    // rules that couldn't be checked
+    pub async fn build_cosmos_msg(
+        &self,
+        role: ContractRole,
+        nft: Option<&str>,
+        contract_addr: String,
+        execute_msg: &str,
+        payment: Option<String>,
+        payment_denom: Option<String>,
+    ) -> Result<(CosmosMsg, ContractMatch, Vec<String>), AmburError> {
+        let (execute_msg, warnings) = match role {
+            ContractRole::Marketplace => {
+                self.prepare_marketplace_msg(&contract_addr, execute_msg)
+                    .await?
+            }
+            _ => (execute_msg.to_string(), vec![]),
+        };
+        let (cosmos_msg, contract) = self.wrap_cosmos_msg(
+            role,
+            nft,
+            contract_addr,
+            &execute_msg,
+            payment,
+            payment_denom,
+        )?;
+        Ok((cosmos_msg, contract, warnings))
+    }
+
+    // build_cosmos_msg without preparing marketplace messages, for messages that
+    // were already prepared
+    fn wrap_cosmos_msg(
+        &self,
+        role: ContractRole,
+        nft: Option<&str>,
+        contract_addr: String,
+        execute_msg: &str,
+        payment: Option<String>,
+        payment_denom: Option<String>,
+    ) -> Result<(CosmosMsg, ContractMatch), AmburError> {
+        let contract = self.resolve_contract(&contract_addr, role, nft, None)?;
+        let network = contract.network.as_ref();
+        let funds = parse_funds(&self.registry, network, payment, payment_denom)?;
+        let msg = execute_binary(&self.schemas, role, nft, execute_msg)?;
+        let cosmos_msg = WasmMsg::Execute {
+            contract_addr,
+            msg,
+            funds,
+        }
+        .into();
+        Ok((cosmos_msg, contract))
+    }
+
+    // Validate `msg` against the loaded schema of `contract`, and serialize it
+    // for a WasmQuery or WasmMsg
+    fn schema_binary(
+        &self,
+        contract: &str,
+        kind: MsgKind,
+        msg: &str,
+    ) -> Result<Binary, AmburError> {
+        find_schema(&self.schemas, contract)?.msg_binary(kind, msg)
+    }
+
+    // One query or execute entry point of a contract, looked up in the
+    // ExecuteMsg and then the QueryMsg unless `msg_type` is given
+    pub fn entry_point(
+        &self,
+        contract_kind: ContractRole,
+        nft: Option<&str>,
+        variant: &str,
+        msg_type: Option<MsgKind>,
+    ) -> Result<EntryPointDescription, AmburError> {
+        let kinds = match msg_type {
+            Some(kind) => vec![kind],
+            None => vec![MsgKind::Execute, MsgKind::Query],
+        };
+        let mut schemas = vec![];
+        for kind in kinds {
+            schemas.push((
+                kind,
+                entry_point_schema(&self.schemas, contract_kind, nft, kind)?,
+            ));
+        }
+        let (kind, root) = match schemas.iter().find(|(_, root)| has_variant(root, variant)) {
+            Some(found) => found,
+            // A single message type reports the variants it does have
+            None if schemas.len() == 1 => &schemas[0],
+            None => {
+                return Err(AmburError::new(
+                    ErrorCode::InvalidMessage,
+                    format!(
+                        "`{variant}` isn't an execute or query entry point of the {contract_kind:?} contract"
+                    ),
+                ));
+            }
+        };
+        // Only token and minter contracts belong to a collection
+        let nft =
+            nft.filter(|_| matches!(contract_kind, ContractRole::Token | ContractRole::Minter));
+        let expected_type = match nft {
+            Some(nft) => format!("{nft} {contract_kind:?} {kind:?}Msg"),
+            None => format!("{contract_kind:?} {kind:?}Msg"),
+        };
+        Ok(EntryPointDescription {
+            contract_kind,
+            nft: nft.map(str::to_string),
+            msg_type: *kind,
+            entry_point: describe_variant(root, variant, expected_type)?,
+        })
+    }
+
+    // A wasm smart QueryRequest for a contract with a loaded schema
+    pub fn schema_query_request(
+        &self,
+        contract: &str,
+        contract_addr: String,
+        query_msg: &str,
+    ) -> Result<(QueryRequest, ContractMatch), AmburError> {
+        let contract_match = resolve_any_address(&contract_addr, None, &self.registry)?;
+        let msg = self.schema_binary(contract, MsgKind::Query, query_msg)?;
+        let query_request = QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg });
+        Ok((query_request, contract_match))
+    }
+
+    // Run a query to a contract with a loaded schema, validating the response
+    // when the schema has a response schema for the query
+    pub async fn schema_query(
+        &self,
+        contract: &str,
+        contract_addr: String,
+        query_msg: String,
+        network: Option<ArchwayNetwork>,
+    ) -> Result<QueryResult, AmburError> {
+        let contract_match = resolve_any_address(&contract_addr, network.as_ref(), &self.registry)?;
+        let msg = self.schema_binary(contract, MsgKind::Query, &query_msg)?;
+        let response = self.run_query(&contract_match, network, &msg).await?;
+        let response_type =
+            find_schema(&self.schemas, contract)?.validate_response(&query_msg, &response)?;
+        Ok(QueryResult {
+            query_msg,
+            contract: contract_match,
+            response_type,
+            notes: vec![],
+            response,
+        })
+    }
+
+    // A wasm execute CosmosMsg for a contract with a loaded schema
+    pub fn schema_cosmos_msg(
+        &self,
+        contract: &str,
+        contract_addr: String,
+        execute_msg: &str,
+        payment: Option<String>,
+        payment_denom: Option<String>,
+    ) -> Result<(CosmosMsg, ContractMatch), AmburError> {
+        let contract_match = resolve_any_address(&contract_addr, None, &self.registry)?;
+        let msg = self.schema_binary(contract, MsgKind::Execute, execute_msg)?;
+        let funds = parse_funds(
+            &self.registry,
+            contract_match.network.as_ref(),
+            payment,
+            payment_denom,
+        )?;
+        let cosmos_msg = WasmMsg::Execute {
+            contract_addr,
+            msg,
+            funds,
+        }
+        .into();
+        Ok((cosmos_msg, contract_match))
+    }
+
+    // Build the CosmosMsg of each entry, in order, preparing marketplace messages
+    // as build_cosmos_msg does. Errors and warnings are reported against the
+    // position of their message
+    pub async fn bundle_msgs(
+        &self,
+        messages: Vec<BundleEntry>,
+    ) -> Result<(Vec<CosmosMsg>, Vec<ContractMatch>, Vec<String>), AmburError> {
+        if messages.is_empty() {
+            return Err(AmburError::new(
+                ErrorCode::InvalidMessage,
+                "A transaction bundle needs at least one message",
+            ));
+        }
+        let mut cosmos_msgs: Vec<CosmosMsg> = vec![];
+        let mut contracts: Vec<ContractMatch> = vec![];
+        let mut warnings: Vec<String> = vec![];
+        for (index, entry) in messages.into_iter().enumerate() {
+            let (cosmos_msg, contract, msg_warnings) = self
+                .build_cosmos_msg(
+                    entry.contract_kind,
+                    entry.nft.as_deref(),
+                    entry.contract_addr,
+                    &entry.execute_msg,
+                    entry.payment,
+                    entry.payment_denom,
+                )
+                .await
+                .map_err(|err| AmburError {
+                    message: format!("Message {index}: {}", err.message),
+                    ..err
+                })?;
+            cosmos_msgs.push(cosmos_msg);
+            contracts.push(contract);
+            warnings.extend(
+                msg_warnings
+                    .into_iter()
+                    .map(|warning| format!("Message {index}: {warning}")),
+            );
+        }
+        Ok((cosmos_msgs, contracts, warnings))
+    }
+
+    async fn build_bundle(
+        &self,
+        messages: Vec<BundleEntry>,
+        sender: Option<String>,
+        memo: Option<String>,
+    ) -> Result<ValidatedBundle, AmburError> {
+        let (cosmos_msgs, contracts, warnings) = self.bundle_msgs(messages).await?;
+        let tx_body = sender
+            .map(|sender| build_tx_body(&sender, &cosmos_msgs, &memo.unwrap_or_default(), 0))
+            .transpose()?;
+        let cosmos_msgs = cosmos_msgs
+            .iter()
+            .map(|cosmos_msg| {
+                serde_json::to_string(cosmos_msg).map_err(|_| {
+                    AmburError::serialization::<CosmosMsg>("Error wrapping ExecuteMsg as CosmosMsg")
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(ValidatedBundle {
+            cosmos_msgs,
+            contracts,
+            tx_body,
+            warnings,
+        })
+    }
+
+    pub async fn sale_listing(
+        &self,
+        nft: &str,
+        token_id: String,
+        price: &str,
+        expires: Option<String>,
+        network: ArchwayNetwork,
+        sender: Option<String>,
+    ) -> Result<PreparedTx, AmburError> {
+        let (collection, token) = self.registry.token(nft, &network)?;
+        let marketplace = self.registry.marketplace(&network)?;
+        let usdc = sale_payment_token(&self.registry, &network)?;
+        let amount = parse_price(&self.registry, price, usdc, "sale listings")?;
+        let expires = self.expires(expires, &network).await?;
+        let swap_id = new_swap_id();
+        let execute_msgs = vec![
+            approve_msg(&marketplace.contract_address, &token_id, &expires),
+            create_swap_msg(
+                &swap_id,
+                &token.contract_address,
+                &token_id,
+                &usdc.token,
+                amount,
+                "Sale",
+                &expires,
+            ),
+        ];
+        let entries = vec![
+            BundleEntry {
+                contract_kind: ContractRole::Token,
+                nft: Some(collection.name.clone()),
+                contract_addr: token.contract_address.clone(),
+                execute_msg: execute_msgs[0].to_string(),
+                payment: None,
+                payment_denom: None,
+            },
+            BundleEntry {
+                contract_kind: ContractRole::Marketplace,
+                nft: None,
+                contract_addr: marketplace.contract_address.clone(),
+                execute_msg: execute_msgs[1].to_string(),
+                payment: None,
+                payment_denom: None,
+            },
+        ];
+        let bundle = self.build_bundle(entries, sender, None).await?;
+        let summary = format!(
+            "List {} #{token_id} for sale on the Ambur marketplace ({network:?}) for {} ({amount} {}). \
+            The listing {}, and its swap id is {swap_id}. The marketplace contract ({}) is approved to transfer the NFT to the buyer when it sells.",
+            collection.name,
+            usdc.format(amount),
+            usdc.token.id(),
+            describe_expiration(&expires),
+            marketplace.contract_address,
+        );
+        Ok(PreparedTx {
+            summary,
+            swap_id: Some(swap_id),
+            execute_msgs,
+            bundle,
+        })
+    }
+
+    // The swap's details are fetched from the network when they aren't supplied
+    pub async fn swap_details(
+        &self,
+        swap_id: &str,
+        network: ArchwayNetwork,
+        details: Option<String>,
+    ) -> Result<SwapDetails, AmburError> {
+        let marketplace = self.registry.marketplace(&network)?;
+        let data = match details {
+            Some(details) => parse_raw_response(&details)?,
+            None => {
+                let contract = self.resolve_contract(
+                    &marketplace.contract_address,
+                    ContractRole::Marketplace,
+                    None,
+                    Some(&network),
+                )?;
+                let msg = to_json_binary(&json!({ "details": { "id": swap_id } }))
+                    .map_err(|err| AmburError::serialization::<Binary>(err.to_string()))?;
+                self.run_query(&contract, Some(network.clone()), &msg)
+                    .await?
+            }
+        };
+        from_json_value(decode_marketplace_response("DetailsResponse", data)?)
+    }
+
+    // New swap ids are confirmed unused with the marketplace's `details` query,
+    // which fails with the contract's not found error for its swap storage
+    // (CW721Swap) when the id is unused. Any other failure means the id couldn't
+    // be checked. Returns whether the id could be checked, which requires live
+    // queries
+    async fn check_swap_id(
+        &self,
+        swap_id: &str,
+        network: &ArchwayNetwork,
+    ) -> Result<bool, AmburError> {
+        if !self.live_queries {
+            return Ok(false);
+        }
+        match self.swap_details(swap_id, network.clone(), None).await {
+            Ok(_) => Err(AmburError::new(
+                ErrorCode::SwapIdInUse,
+                format!(
+                    "Swap id {swap_id} is already used on {network:?}, generate another one (see 'generate_swap_id', with a different nonce for deterministic ids)"
+                ),
+            )),
+            Err(err) if lcd_error_body(&err).is_some_and(|body| is_swap_not_found(&body)) => {
+                Ok(true)
+            }
+            Err(err) => Err(AmburError::new(
+                err.code,
+                format!(
+                    "Swap id {swap_id} couldn't be checked on {network:?}: {}",
+                    err.message
+                ),
+            )),
+        }
+    }
+
+    // Random ids, or deterministic ids when the seller is given
+    async fn swap_id(
+        &self,
+        seller: Option<String>,
+        nft: Option<String>,
+        token_id: Option<String>,
+        nonce: Option<String>,
+        network: Option<ArchwayNetwork>,
+    ) -> Result<GeneratedSwapId, AmburError> {
+        let (swap_id, kind) = match (seller, nft) {
+            (None, None) if token_id.is_none() && nonce.is_none() => (new_swap_id(), "random"),
+            (Some(seller), Some(nft)) => {
+                let cw721 = match network
+                    .as_ref()
+                    .map(|network| self.registry.token(&nft, network))
+                {
+                    Some(Ok((_, token))) => token.contract_address.clone(),
+                    _ if lookup_address(&nft, &self.registry)
+                        .iter()
+                        .any(|(_, role, _)| role == &ContractRole::Token) =>
+                    {
+                        nft
+                    }
+                    _ => return Err(AmburError::unknown_collection(&nft)),
+                };
+                let nonce = nonce.unwrap_or("0".to_string());
+                (
+                    deterministic_swap_id(seller.trim(), &cw721, token_id.as_deref(), &nonce),
+                    "deterministic",
+                )
+            }
+            _ => {
+                return Err(AmburError::new(
+                    ErrorCode::InvalidMessage,
+                    "Deterministic swap ids need both the 'seller' and the 'nft', leave out all the parameters for a random id",
+                ));
+            }
+        };
+        let checked = match &network {
+            Some(network) => self.check_swap_id(&swap_id, network).await?,
+            None => false,
+        };
+        Ok(GeneratedSwapId {
+            swap_id,
+            kind: kind.to_string(),
+            checked,
+        })
+    }
+
+    // Height based expirations can only be checked against the chain's current
+    // height when live queries are enabled
+    async fn check_not_expired(
+        &self,
+        swap_id: &str,
+        network: &ArchwayNetwork,
+        expires: Option<&Expiration>,
+    ) -> Result<(), AmburError> {
+        let expired = match expires {
+            Some(Expiration::AtTime(time)) if *time <= to_timestamp(Utc::now()) => {
+                let at = to_datetime(*time).map(rfc3339).unwrap_or_default();
+                Some(format!("expired at {at}"))
+            }
+            Some(Expiration::AtHeight(height)) if self.live_queries => {
+                let current = self.lcd(network)?.latest_block_height().await?;
+                (*height <= current).then(|| {
+                    format!("expired at block height {height} (the current height is {current})")
+                })
+            }
+            _ => None,
+        };
+        match expired {
+            Some(expired) => Err(AmburError::new(
+                ErrorCode::InvalidExpiration,
+                format!("Swap {swap_id} {expired}, expired listings can't be purchased"),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    pub async fn purchase(
+        &self,
+        swap_id: &str,
+        network: ArchwayNetwork,
+        details: Option<String>,
+        sender: Option<String>,
+    ) -> Result<PreparedTx, AmburError> {
+        let marketplace = self.registry.marketplace(&network)?;
+        let details = self.swap_details(swap_id, network.clone(), details).await?;
+        if details.swap_type != "Sale" {
+            return Err(AmburError::new(
+                ErrorCode::InvalidMessage,
+                format!(
+                    "Swap {swap_id} is an {}, only Sale swaps can be purchased",
+                    details.swap_type
+                ),
+            ));
+        }
+        if sender.as_deref() == Some(details.creator.as_str()) {
+            return Err(AmburError::new(
+                ErrorCode::InvalidMessage,
+                format!("Swap {swap_id} was created by the sender, who can't purchase it"),
+            ));
+        }
+        self.check_not_expired(swap_id, &network, details.expires.as_ref())
+            .await?;
+        let finish = finish_swap_msg(swap_id);
+        let (execute_msgs, entries) = match &details.payment_token {
+            SwapPaymentToken::Native { denom } => (
+                vec![finish.clone()],
+                vec![BundleEntry {
+                    contract_kind: ContractRole::Marketplace,
+                    nft: None,
+                    contract_addr: marketplace.contract_address.clone(),
+                    execute_msg: finish.to_string(),
+                    payment: Some(details.price.to_string()),
+                    payment_denom: Some(denom.clone()),
+                }],
+            ),
+            SwapPaymentToken::Cw20 { address } => {
+                let allowance =
+                    increase_allowance_msg(&marketplace.contract_address, details.price);
+                (
+                    vec![allowance.clone(), finish.clone()],
+                    vec![
+                        BundleEntry {
+                            contract_kind: ContractRole::Cw20,
+                            nft: None,
+                            contract_addr: address.clone(),
+                            execute_msg: allowance.to_string(),
+                            payment: None,
+                            payment_denom: None,
+                        },
+                        BundleEntry {
+                            contract_kind: ContractRole::Marketplace,
+                            nft: None,
+                            contract_addr: marketplace.contract_address.clone(),
+                            execute_msg: finish.to_string(),
+                            payment: None,
+                            payment_denom: None,
+                        },
+                    ],
+                )
+            }
+        };
+        let bundle = self.build_bundle(entries, sender, None).await?;
+        let nft = match details.contract.as_deref() {
+            Some(contract) => match lookup_address_on(contract, &self.registry, Some(&network)) {
+                Some((_, _, Some(collection))) => collection,
+                _ => format!("NFT contract {contract}"),
+            },
+            None => "NFT".to_string(),
+        };
+        let payment = describe_payment(
+            &self.registry,
+            &network,
+            details.price,
+            &details.payment_token,
+        );
+        let payment_step = match &details.payment_token {
+            SwapPaymentToken::Native { .. } => {
+                format!("The {payment} are sent with the marketplace `finish` message.")
+            }
+            SwapPaymentToken::Cw20 { .. } => format!(
+                "The marketplace contract ({}) is first allowed to spend {payment} of the buyer's cw20 balance, then the swap is finished.",
+                marketplace.contract_address
+            ),
+        };
+        let summary = format!(
+            "Buy {nft} #{} from {} on the Ambur marketplace ({network:?}) for {payment}, by finishing swap {swap_id}. {payment_step}",
+            details.token_id.as_deref().unwrap_or("?"),
+            details.creator,
+        );
+        Ok(PreparedTx {
+            summary,
+            swap_id: Some(swap_id.to_string()),
+            execute_msgs,
+            bundle,
+        })
+    }
+
+    // Offers are paid in wUSDC, so the marketplace is allowed to spend the
+    // offered amount before the swap is created
+    pub async fn offer(
+        &self,
+        nft: &str,
+        token_id: String,
+        price: &str,
+        expires: Option<String>,
+        network: ArchwayNetwork,
+        sender: Option<String>,
+    ) -> Result<PreparedTx, AmburError> {
+        let (collection, token) = self.registry.token(nft, &network)?;
+        let marketplace = self.registry.marketplace(&network)?;
+        let wusdc = offer_payment_token(&self.registry, &network)?;
+        let amount = parse_price(&self.registry, price, wusdc, "offers")?;
+        let expires = self.expires(expires, &network).await?;
+        let swap_id = new_swap_id();
+        let execute_msgs = vec![
+            increase_allowance_msg(&marketplace.contract_address, amount),
+            create_swap_msg(
+                &swap_id,
+                &token.contract_address,
+                &token_id,
+                &wusdc.token,
+                amount,
+                "Offer",
+                &expires,
+            ),
+        ];
+        let bundle = self
+            .build_bundle(
+                wusdc_offer_entries(
+                    wusdc.token.id(),
+                    &marketplace.contract_address,
+                    &execute_msgs,
+                ),
+                sender,
+                None,
+            )
+            .await?;
+        let summary = format!(
+            "Offer {} ({amount} of cw20 token {}) for {} #{token_id} on the Ambur marketplace ({network:?}). \
+            The offer {}, and its swap id is {swap_id}. The marketplace contract ({}) is first allowed to spend the offered {}, \
+            which stays in the offerer's balance until the owner accepts the offer.",
+            wusdc.format(amount),
+            wusdc.token.id(),
+            collection.name,
+            describe_expiration(&expires),
+            marketplace.contract_address,
+            wusdc.symbol,
+        );
+        Ok(PreparedTx {
+            summary,
+            swap_id: Some(swap_id),
+            execute_msgs,
+            bundle,
+        })
+    }
+
+    // Collection offers are always priced with the total for the whole batch
+    pub async fn collection_offer(
+        &self,
+        request: CollectionOfferRequest,
+    ) -> Result<PreparedTx, AmburError> {
+        let CollectionOfferRequest {
+            nft,
+            quantity,
+            price_per_nft,
+            total_price,
+            expires,
+            network,
+            sender,
+        } = request;
+        let (collection, token) = self.registry.token(&nft, &network)?;
+        let marketplace = self.registry.marketplace(&network)?;
+        let wusdc = offer_payment_token(&self.registry, &network)?;
+        let total =
+            collection_offer_total(&self.registry, wusdc, quantity, price_per_nft, total_price)?;
+        let expires = self.expires(expires, &network).await?;
+        let swap_id = new_swap_id();
+        let execute_msgs = vec![
+            increase_allowance_msg(&marketplace.contract_address, total),
+            create_collection_offer_msg(
+                &swap_id,
+                &token.contract_address,
+                &wusdc.token,
+                total,
+                quantity,
+                &expires,
+            ),
+        ];
+        let bundle = self
+            .build_bundle(
+                wusdc_offer_entries(
+                    wusdc.token.id(),
+                    &marketplace.contract_address,
+                    &execute_msgs,
+                ),
+                sender,
+                None,
+            )
+            .await?;
+        let summary = format!(
+            "Offer {} in total ({total} of cw20 token {}) for {quantity} NFT(s) from the {} collection on the Ambur marketplace ({network:?}), \
+            about {} per NFT. Sellers can fill the offer with any token ids. The offer {}, and its swap id is {swap_id}. \
+            The marketplace contract ({}) is first allowed to spend the offered {}, which stays in the offerer's balance until the offer is filled.",
+            wusdc.format(total),
+            wusdc.token.id(),
+            collection.name,
+            wusdc.format(total / Uint128::from(quantity)),
+            describe_expiration(&expires),
+            marketplace.contract_address,
+            wusdc.symbol,
+        );
+        Ok(PreparedTx {
+            summary,
+            swap_id: Some(swap_id),
+            execute_msgs,
+            bundle,
+        })
+    }
+
+    // Human prices and expirations are converted, and missing swap ids
+    // generated, first so that the rules see the message as it's sent.
+    // Returns the marketplace ExecuteMsg that passed the rules, and warnings
+    // of the rules that couldn't be checked
+    pub async fn prepare_marketplace_msg(
+        &self,
+        contract_addr: &str,
+        execute_msg: &str,
+    ) -> Result<(String, Vec<String>), AmburError> {
+        let contract =
+            self.resolve_contract(contract_addr, ContractRole::Marketplace, None, None)?;
+        let execute_msg = normalize_price(&self.registry, contract.network.as_ref(), execute_msg)?;
+        let execute_msg = fill_swap_id(&normalize_expires(&execute_msg)?)?;
+        let warnings = self
+            .check_marketplace_rules(contract_addr, &execute_msg)
+            .await?;
+        Ok((execute_msg, warnings))
+    }
+
+    // Updates of Offers and the ids of new swaps are only checked with live
+    // queries, since the swaps' current details are needed
+    async fn check_marketplace_rules(
+        &self,
+        contract_addr: &str,
+        execute_msg: &str,
+    ) -> Result<Vec<String>, AmburError> {
+        let contract =
+            self.resolve_contract(contract_addr, ContractRole::Marketplace, None, None)?;
+        let msg = from_json_str::<ExecuteMsg>(execute_msg)?;
+        let rules = MarketplaceRules::new(&self.registry, contract.network.clone());
+        rules.check(&msg)?;
+        let mut warnings = vec![];
+        if let Some(swap_id) = rules.updated_swap(&msg)? {
+            match &contract.network {
+                Some(network) if self.live_queries => {
+                    let current = self.swap_details(&swap_id, network.clone(), None).await?;
+                    rules.check_update(&msg, &current)?;
+                }
+                _ => warnings.push(format!(
+                    "The update of swap {swap_id} wasn't checked against its current details (this needs live queries): if it's an Offer, only its expiration can be updated and the price must stay the same"
+                )),
+            }
         }
+        if let Some((path, swap_id)) = rules.created_swap(&msg)?
+            && let Some(network) = &contract.network
+        {
+            self.check_swap_id(&swap_id, network)
+                .await
+                .map_err(|err| AmburError {
+                    path: Some(path),
+                    ..err
+                })?;
+        }
+        Ok(warnings)
+    }
+
+    pub fn sign_doc(&self, request: SignDocRequest) -> Result<EncodedSignDoc, AmburError> {
+        let SignDocRequest {
+            sign_mode,
+            network,
+            sender,
+            cosmos_msgs,
+            account_number,
+            sequence,
+            fee_amount,
+            fee_denom,
+            gas_limit,
+            memo,
+            timeout_height,
+            public_key,
+        } = request;
+        let config = self.network_config(&network)?;
+        let amount =
+            Uint128::from_str(&fee_amount).map_err(|_| AmburError::invalid_amount(&fee_amount))?;
+        let msgs = cosmos_msgs
+            .iter()
+            .map(|cosmos_msg| from_json_str::<CosmosMsg>(cosmos_msg))
+            .collect::<Result<Vec<_>, _>>()?;
+        let params = SignDocParams {
+            chain_id: config.chain_id.clone(),
+            account_number,
+            sequence,
+            fee: vec![Coin {
+                denom: fee_denom.unwrap_or(config.fee_denom.clone()),
+                amount,
+            }],
+            gas_limit,
+            memo: memo.unwrap_or_default(),
+            timeout_height: timeout_height.unwrap_or_default(),
+            public_key,
+        };
+        build_sign_doc(sign_mode, &sender, &msgs, &params)
+    }
+
+    fn network_config(&self, network: &ArchwayNetwork) -> Result<&NetworkConfig, AmburError> {
+        self.registry.network(network).ok_or_else(|| {
+            AmburError::new(
+                ErrorCode::InvalidNetwork,
+                format!("Network {network:?} isn't configured"),
+            )
+        })
+    }
+
+    // The sender must be the signing key's address on the requested network
+    #[cfg(feature = "signing")]
+    pub fn sign(&self, mut request: SignDocRequest) -> Result<SignedTx, AmburError> {
+        let Some(signer) = &self.signer else {
+            return Err(AmburError::new(
+                ErrorCode::SigningDisabled,
+                "Signing is disabled, restart the server with --signing and a signing key to enable it",
+            ));
+        };
+        let config = self.network_config(&request.network)?;
+        let address = signer.address(&config.bech32_prefix)?;
+        if request.sender != address {
+            return Err(AmburError::new(
+                ErrorCode::AddressMismatch,
+                format!(
+                    "Sender {} isn't the address of the signing key ({address})",
+                    request.sender
+                ),
+            ));
+        }
+        let public_key = signer.public_key();
+        match &request.public_key {
+            Some(requested) if requested != &public_key => {
+                return Err(AmburError::new(
+                    ErrorCode::AddressMismatch,
+                    format!("Public key {requested} isn't the public key of the signing key"),
+                ));
+            }
+            _ => request.public_key = Some(public_key),
+        }
+        signer.sign(self.sign_doc(request)?)
+    }
+
+    #[cfg(feature = "signing")]
+    pub fn signing_enabled(&self) -> bool {
+        self.signer.is_some()
+    }
+
+    #[cfg(not(feature = "signing"))]
+    pub fn signing_enabled(&self) -> bool {
+        false
+    }
+
+    #[cfg(not(feature = "signing"))]
+    pub fn sign(&self, _request: SignDocRequest) -> Result<Value, AmburError> {
+        Err(AmburError::new(
+            ErrorCode::SigningDisabled,
+            "Signing isn't compiled in, rebuild the server with `--features signing` to enable it",
+        ))
     }
 
     // Contracts and collections tools
     #[tool(description = LIST_CONTRACTS_DESCR)]
     async fn list_contract_deployments(&self) -> Result<CallToolResult, Error> {
-        let serialized: String = serde_json::to_string(&self.contracts).unwrap_or("".to_string());
+        let serialized: String =
+            serde_json::to_string(&self.registry.contracts).unwrap_or("".to_string());
         Ok(CallToolResult::success(vec![Content::text(serialized)]))
     }
 
-    #[tool(description = LIST_NFT_COLLECTIONS_DESCR)]
-    async fn list_nft_collections(&self) -> Result<CallToolResult, Error> {
-        let serialized: String = serde_json::to_string(&self.collections).unwrap_or("".to_string());
+    #[tool(description = LIST_NETWORKS_DESCR)]
+    async fn list_networks(&self) -> Result<CallToolResult, Error> {
+        let serialized: String =
+            serde_json::to_string(&self.registry.networks).unwrap_or("".to_string());
         Ok(CallToolResult::success(vec![Content::text(serialized)]))
     }
 
-    // Query entry point tools
-    #[tool(description = LIST_QUERY_ENTRY_POINTS_DESCR)]
-    async fn list_query_entry_points(&self) -> Result<CallToolResult, Error> {
-        let schema = schema_for!(QueryMsg);
-        let serialized: String = serde_json::to_string(&schema).unwrap_or("".to_string());
+    #[tool(description = LIST_PAYMENT_TOKENS_DESCR)]
+    async fn list_payment_tokens(&self) -> Result<CallToolResult, Error> {
+        let serialized: String =
+            serde_json::to_string(&self.registry.payment_tokens).unwrap_or("".to_string());
         Ok(CallToolResult::success(vec![Content::text(serialized)]))
     }
 
-    #[tool(description = LIST_QUERY_RESPONSE_DESCR)]
-    async fn list_query_responses(&self) -> Result<CallToolResult, Error> {
-        let schema = schema_for!(AllQueryResponse);
-        let serialized: String = serde_json::to_string(&schema).unwrap_or("".to_string());
+    #[tool(description = PARSE_AMOUNT_DESCR)]
+    async fn parse_amount(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "human amount, with or without its symbol (e.g. \"8.88 USDC\" or \"8.88\"); whole numbers need their symbol or a decimal point (e.g. \"8 USDC\" or \"8.0\")"
+        )]
+        amount: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally include the payment token's symbol, denom or cw20 address, required when the amount has no symbol"
+        )]
+        token: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally include the network of the payment token (e.g. \"Mainnet\"), required when the token is registered on more than one network"
+        )]
+        network: Option<ArchwayNetwork>,
+    ) -> Result<CallToolResult, Error> {
+        let parsed = match self.parse_amount_in(&amount, token.as_deref(), network.as_ref()) {
+            Ok(parsed) => parsed,
+            Err(err) => return Ok(err.into()),
+        };
+        let serialized: String = serde_json::to_string(&parsed).unwrap_or_default();
         Ok(CallToolResult::success(vec![Content::text(serialized)]))
     }
 
-    #[tool(description = BUILD_QUERY_MSG_DESCR)]
-    async fn build_query_msg(
+    #[tool(description = FORMAT_AMOUNT_DESCR)]
+    async fn format_amount(
         &self,
         #[tool(param)]
+        #[schemars(description = "amount in base units (e.g. \"8880000\")")]
+        amount: String,
+        #[tool(param)]
+        #[schemars(description = "payment token's symbol, denom or cw20 address (e.g. \"USDC\")")]
+        token: String,
+        #[tool(param)]
         #[schemars(
-            description = "contract address of Ambur marketplace (e.g. mainnet or testnet address)"
+            description = "Optionally include the network of the payment token (e.g. \"Mainnet\"), required when the token is registered on more than one network"
         )]
-        contract_addr: String,
+        network: Option<ArchwayNetwork>,
+    ) -> Result<CallToolResult, Error> {
+        let formatted = match self.format_amount_in(&amount, &token, network.as_ref()) {
+            Ok(formatted) => formatted,
+            Err(err) => return Ok(err.into()),
+        };
+        let serialized: String = serde_json::to_string(&formatted).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = GENERATE_SWAP_ID_DESCR)]
+    async fn generate_swap_id(
+        &self,
         #[tool(param)]
         #[schemars(
-            description = "JSON stringified QueryMsg variant needed for building the query as a Cosmos SDK QueryRequest"
+            description = "Optionally include the seller's (or offerer's) address to generate a deterministic id, requires the 'nft'"
         )]
-        query_msg: String,
+        seller: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally include the name of the NFT collection or its cw721 contract address, for deterministic ids"
+        )]
+        nft: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally include the token id of the NFT, for deterministic ids (leave out for collection offers)"
+        )]
+        token_id: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally include a nonce for deterministic ids (e.g. \"1\"), defaults to \"0\"; change it to make another swap for the same NFT"
+        )]
+        nonce: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally include the network (e.g. \"Mainnet\") to check that the id is unused (requires live queries to be enabled)"
+        )]
+        network: Option<ArchwayNetwork>,
     ) -> Result<CallToolResult, Error> {
-        let deserialized: QueryMsg = serde_json::from_str(query_msg.as_str()).unwrap();
-        let query_req: QueryRequest<QueryMsg> = QueryRequest::Wasm(WasmQuery::Smart {
-            contract_addr,
-            msg: to_json_binary(&deserialized).unwrap_or_default(),
-        });
-        let serialized_query_req = serde_json::to_string(&query_req);
-        if serialized_query_req.is_err() {
-            return Ok(CallToolResult::error(vec![Content::text(
-                "Error wrapping QueryMsg as QueryRequest",
-            )]));
-        }
-        let valid_query = ValidatedQuery {
-            query_msg,
-            query_request: serialized_query_req.unwrap_or_default(),
+        let generated = match self.swap_id(seller, nft, token_id, nonce, network).await {
+            Ok(generated) => generated,
+            Err(err) => return Ok(err.into()),
         };
-        let serialized: String = serde_json::to_string(&valid_query).unwrap_or_default();
+        let serialized: String = serde_json::to_string(&generated).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = BUILD_EXPIRATION_DESCR)]
+    async fn build_expiration(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "when the swap or approval expires: a duration (e.g. \"in 7 days\", \"in 12 hours\", \"in 1000 blocks\"), an RFC 3339 time or date (e.g. \"2026-12-31T18:00:00Z\" or \"2026-12-31\"), a block height (e.g. \"height 1234567\"), \"never\", or a JSON Expiration"
+        )]
+        input: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally include the network (e.g. \"Mainnet\") to resolve block counts and estimate when a block height is reached (the current height requires live queries to be enabled)"
+        )]
+        network: Option<ArchwayNetwork>,
+    ) -> Result<CallToolResult, Error> {
+        let built = match self.expiration(&input, network.as_ref()).await {
+            Ok(built) => built,
+            Err(err) => return Ok(err.into()),
+        };
+        let serialized: String = serde_json::to_string(&built).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = LIST_NFT_COLLECTIONS_DESCR)]
+    async fn list_nft_collections(&self) -> Result<CallToolResult, Error> {
+        let serialized: String =
+            serde_json::to_string(&self.registry.collections).unwrap_or("".to_string());
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    // Query entry point tools
+    #[tool(description = LIST_QUERY_ENTRY_POINTS_DESCR)]
+    async fn list_query_entry_points(&self) -> Result<CallToolResult, Error> {
+        let schema = schema_for!(QueryMsg);
+        let serialized: String = serde_json::to_string(&schema).unwrap_or("".to_string());
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = LIST_QUERY_RESPONSE_DESCR)]
+    async fn list_query_responses(&self) -> Result<CallToolResult, Error> {
+        let schema = schema_for!(AllQueryResponse);
+        let serialized: String = serde_json::to_string(&schema).unwrap_or("".to_string());
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = DECODE_QUERY_RESPONSE_DESCR)]
+    async fn decode_query_response(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "kind of contract that was queried (\"marketplace\", \"token\" or \"minter\")"
+        )]
+        contract_kind: ContractRole,
+        #[tool(param)]
+        #[schemars(with = "Option<ContractCollectionName>")]
+        nft: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "JSON stringified QueryMsg variant that was queried")]
+        query_msg: String,
+        #[tool(param)]
+        #[schemars(description = "raw query response, as JSON or as base64 encoded JSON bytes")]
+        response: String,
+    ) -> Result<CallToolResult, Error> {
+        if matches!(contract_kind, ContractRole::Token | ContractRole::Minter) && nft.is_none() {
+            return Ok(AmburError::new(
+                ErrorCode::UnknownCollection,
+                "An NFT collection name is required to decode token and minter query responses",
+            )
+            .into());
+        }
+        let data = match parse_raw_response(&response) {
+            Ok(data) => data,
+            Err(err) => return Ok(err.into()),
+        };
+        let (response_type, response) = match decode_query(contract_kind, &query_msg, data) {
+            Ok(decoded) => decoded,
+            Err(err) => return Ok(err.into()),
+        };
+        let decoded = DecodedResponse {
+            query_msg,
+            validated: response_type.is_some(),
+            notes: response_notes(response_type.as_deref()),
+            response_type,
+            response,
+        };
+        let serialized: String = serde_json::to_string(&decoded).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = BUILD_QUERY_MSG_DESCR)]
+    async fn build_query_msg(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "contract address of Ambur marketplace (e.g. mainnet or testnet address)"
+        )]
+        contract_addr: String,
+        #[tool(param)]
+        #[schemars(
+            description = "JSON stringified QueryMsg variant needed for building the query as a Cosmos SDK QueryRequest"
+        )]
+        query_msg: String,
+    ) -> Result<CallToolResult, Error> {
+        let valid_query = match self
+            .query_request(ContractRole::Marketplace, None, contract_addr, &query_msg)
+            .and_then(|(query_request, contract)| {
+                validated_query(query_msg, &query_request, contract)
+            }) {
+            Ok(valid_query) => valid_query,
+            Err(err) => return Ok(err.into()),
+        };
+        let serialized: String = serde_json::to_string(&valid_query).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = EXECUTE_QUERY_DESCR)]
+    async fn execute_query(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "contract address of Ambur marketplace (e.g. mainnet or testnet address)"
+        )]
+        contract_addr: String,
+        #[tool(param)]
+        #[schemars(description = "JSON stringified QueryMsg variant to be queried")]
+        query_msg: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network to query (only required if the contract address isn't registered)"
+        )]
+        network: Option<ArchwayNetwork>,
+    ) -> Result<CallToolResult, Error> {
+        let result = match self
+            .query(
+                ContractRole::Marketplace,
+                None,
+                contract_addr,
+                query_msg,
+                network,
+            )
+            .await
+        {
+            Ok(result) => result,
+            Err(err) => return Ok(err.into()),
+        };
+        let serialized: String = serde_json::to_string(&result).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    // Execute entry point tools
+    #[tool(description = LIST_TX_ENTRY_POINTS_DESCR)]
+    async fn list_tx_entry_points(&self) -> Result<CallToolResult, Error> {
+        let schema = schema_for!(ExecuteMsg);
+        let serialized: String = serde_json::to_string(&schema).unwrap_or("".to_string());
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = DESCRIBE_ENTRY_POINT_DESCR)]
+    async fn describe_entry_point(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "kind of contract the entry point belongs to (\"marketplace\", \"token\", \"minter\" or \"cw20\")"
+        )]
+        contract_kind: ContractRole,
+        #[tool(param)]
+        #[schemars(with = "Option<ContractCollectionName>")]
+        nft: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "name of the entry point, e.g. the ExecuteMsg or QueryMsg variant \"create_collection_offer\""
+        )]
+        variant: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally include whether the entry point is an \"execute\" or \"query\" entry point, both are searched (execute first) when omitted"
+        )]
+        msg_type: Option<MsgKind>,
+    ) -> Result<CallToolResult, Error> {
+        let description = match self.entry_point(contract_kind, nft.as_deref(), &variant, msg_type)
+        {
+            Ok(description) => description,
+            Err(err) => return Ok(err.into()),
+        };
+        let serialized: String = serde_json::to_string(&description).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = BUILD_EXECUTE_MSG_DESCR)]
+    async fn build_execute_msg(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "contract address of Ambur marketplace (e.g. mainnet or testnet address)"
+        )]
+        contract_addr: String,
+        #[tool(param)]
+        #[schemars(
+            description = "ExecuteMsg variant and its values needed for building the transaction as a Cosmos SDK CosmosMsg"
+        )]
+        execute_msg: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally include native payment funds to be sent in the transaction (only required for 'finish' txs if payment_token is a native token), in base units or as a human amount (e.g. \"8.88 USDC\")"
+        )]
+        payment: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally include native payment denom (or registered symbol) for funds being sent in the transaction (required for base unit payments; e.g. not needed for \"8.88 USDC\", or for cw20 payments)"
+        )]
+        payment_denom: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally include the sender (signer) address to also encode the message as a MsgExecuteContract protobuf, wrapped in an unsigned TxBody"
+        )]
+        sender: Option<String>,
+    ) -> Result<CallToolResult, Error> {
+        let (execute_msg, warnings) = match self
+            .prepare_marketplace_msg(&contract_addr, &execute_msg)
+            .await
+        {
+            Ok(prepared) => prepared,
+            Err(err) => return Ok(err.into()),
+        };
+        let (cosmos_msg, contract) = match self.wrap_cosmos_msg(
+            ContractRole::Marketplace,
+            None,
+            contract_addr,
+            &execute_msg,
+            payment,
+            payment_denom,
+        ) {
+            Ok(built) => built,
+            Err(err) => return Ok(err.into()),
+        };
+        let valid_execute =
+            match validated_execute(execute_msg, cosmos_msg, contract, sender, warnings) {
+                Ok(valid_execute) => valid_execute,
+                Err(err) => return Ok(err.into()),
+            };
+        let serialized: String = serde_json::to_string(&valid_execute).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = BUILD_TX_BODY_DESCR)]
+    async fn build_tx_body(
+        &self,
+        #[tool(param)]
+        #[schemars(description = "sender (signer) address of the transaction")]
+        sender: String,
+        #[tool(param)]
+        #[schemars(
+            description = "ordered list of JSON stringified CosmosMsg values (e.g. the 'cosmos_msg' field returned by the build_*_execute_msg tools)"
+        )]
+        cosmos_msgs: Vec<String>,
+        #[tool(param)]
+        #[schemars(description = "Optionally include a memo for the transaction")]
+        memo: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally include a block height after which the transaction is no longer valid"
+        )]
+        timeout_height: Option<u64>,
+    ) -> Result<CallToolResult, Error> {
+        let mut msgs: Vec<CosmosMsg> = vec![];
+        for cosmos_msg in &cosmos_msgs {
+            match from_json_str::<CosmosMsg>(cosmos_msg) {
+                Ok(msg) => msgs.push(msg),
+                Err(err) => return Ok(err.into()),
+            }
+        }
+        let tx_body = match build_tx_body(
+            &sender,
+            &msgs,
+            memo.as_deref().unwrap_or_default(),
+            timeout_height.unwrap_or_default(),
+        ) {
+            Ok(tx_body) => tx_body,
+            Err(err) => return Ok(err.into()),
+        };
+        let serialized: String = serde_json::to_string(&tx_body).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = BUILD_TX_BUNDLE_DESCR)]
+    async fn build_tx_bundle(
+        &self,
+        #[tool(param)]
+        #[schemars(
+            description = "ordered list of messages to bundle in a single transaction, they are executed in this order"
+        )]
+        messages: Vec<BundleEntry>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally include the sender (signer) address to also encode the messages as MsgExecuteContract protobufs, wrapped in an unsigned TxBody"
+        )]
+        sender: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "Optionally include a memo for the transaction")]
+        memo: Option<String>,
+    ) -> Result<CallToolResult, Error> {
+        let bundle = match self.build_bundle(messages, sender, memo).await {
+            Ok(bundle) => bundle,
+            Err(err) => return Ok(err.into()),
+        };
+        let serialized: String = serde_json::to_string(&bundle).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = BUILD_SIGN_DOC_DESCR)]
+    async fn build_sign_doc(
+        &self,
+        #[tool(aggr)] request: SignDocRequest,
+    ) -> Result<CallToolResult, Error> {
+        let sign_doc = match self.sign_doc(request) {
+            Ok(sign_doc) => sign_doc,
+            Err(err) => return Ok(err.into()),
+        };
+        let serialized: String = serde_json::to_string(&sign_doc).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = SIGN_TX_DESCR)]
+    async fn sign_tx(
+        &self,
+        #[tool(aggr)] request: SignDocRequest,
+    ) -> Result<CallToolResult, Error> {
+        let signed_tx = match self.sign(request) {
+            Ok(signed_tx) => signed_tx,
+            Err(err) => return Ok(err.into()),
+        };
+        let serialized: String = serde_json::to_string(&signed_tx).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    // cw721 Query entry point tools
+    #[tool(description = LIST_TOKEN_QUERY_ENTRY_POINTS_DESCR)]
+    async fn list_token_query_entry_points(
+        &self,
+        #[tool(param)]
+        #[schemars(with = "CollectionName")]
+        nft: String,
+    ) -> Result<CallToolResult, Error> {
+        let schema = match contract_family(&self.schemas, &nft, ContractRole::Token)
+            .and_then(|family| family.query_schema())
+        {
+            Ok(schema) => schema,
+            Err(err) => return Ok(err.into()),
+        };
+        let serialized: String = serde_json::to_string(&schema).unwrap_or("".to_string());
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = BUILD_TOKEN_QUERY_MSG_DESCR)]
+    async fn build_token_query_msg(
+        &self,
+        #[tool(param)]
+        #[schemars(with = "CollectionName")]
+        nft: String,
+        #[tool(param)]
+        #[schemars(description = "contract address of cw721 token")]
+        contract_addr: String,
+        #[tool(param)]
+        #[schemars(
+            description = "JSON stringified QueryMsg variant needed for building the query as a Cosmos SDK QueryRequest"
+        )]
+        query_msg: String,
+    ) -> Result<CallToolResult, Error> {
+        let valid_query = match self
+            .query_request(ContractRole::Token, Some(&nft), contract_addr, &query_msg)
+            .and_then(|(query_request, contract)| {
+                validated_query(query_msg, &query_request, contract)
+            }) {
+            Ok(valid_query) => valid_query,
+            Err(err) => return Ok(err.into()),
+        };
+        let serialized: String = serde_json::to_string(&valid_query).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = EXECUTE_TOKEN_QUERY_DESCR)]
+    async fn execute_token_query(
+        &self,
+        #[tool(param)]
+        #[schemars(with = "CollectionName")]
+        nft: String,
+        #[tool(param)]
+        #[schemars(description = "contract address of cw721 token")]
+        contract_addr: String,
+        #[tool(param)]
+        #[schemars(description = "JSON stringified QueryMsg variant to be queried")]
+        query_msg: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network to query (only required if the contract address isn't registered)"
+        )]
+        network: Option<ArchwayNetwork>,
+    ) -> Result<CallToolResult, Error> {
+        let result = match self
+            .query(
+                ContractRole::Token,
+                Some(&nft),
+                contract_addr,
+                query_msg,
+                network,
+            )
+            .await
+        {
+            Ok(result) => result,
+            Err(err) => return Ok(err.into()),
+        };
+        let serialized: String = serde_json::to_string(&result).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    // cw721 Execute entry point tools
+    #[tool(description = LIST_TOKEN_TX_ENTRY_POINTS_DESCR)]
+    async fn list_token_tx_entry_points(
+        &self,
+        #[tool(param)]
+        #[schemars(with = "CollectionName")]
+        nft: String,
+    ) -> Result<CallToolResult, Error> {
+        let schema = match contract_family(&self.schemas, &nft, ContractRole::Token)
+            .and_then(|family| family.execute_schema())
+        {
+            Ok(schema) => schema,
+            Err(err) => return Ok(err.into()),
+        };
+        let serialized: String = serde_json::to_string(&schema).unwrap_or("".to_string());
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = BUILD_TOKEN_EXECUTE_MSG_DESCR)]
+    async fn build_token_execute_msg(
+        &self,
+        #[tool(param)]
+        #[schemars(with = "CollectionName")]
+        nft: String,
+        #[tool(param)]
+        #[schemars(description = "contract address of cw721 token")]
+        contract_addr: String,
+        #[tool(param)]
+        #[schemars(
+            description = "ExecuteMsg variant and its values needed for building the transaction as a Cosmos SDK CosmosMsg"
+        )]
+        execute_msg: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally include the sender (signer) address to also encode the message as a MsgExecuteContract protobuf, wrapped in an unsigned TxBody"
+        )]
+        sender: Option<String>,
+    ) -> Result<CallToolResult, Error> {
+        let (cosmos_msg, contract) = match self.wrap_cosmos_msg(
+            ContractRole::Token,
+            Some(&nft),
+            contract_addr,
+            &execute_msg,
+            None,
+            None,
+        ) {
+            Ok(built) => built,
+            Err(err) => return Ok(err.into()),
+        };
+        let valid_execute =
+            match validated_execute(execute_msg, cosmos_msg, contract, sender, vec![]) {
+                Ok(valid_execute) => valid_execute,
+                Err(err) => return Ok(err.into()),
+            };
+        let serialized: String = serde_json::to_string(&valid_execute).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    // Minter Query entry point tools
+    #[tool(description = LIST_MINTER_QUERY_ENTRY_POINTS_DESCR)]
+    async fn list_minter_query_entry_points(
+        &self,
+        #[tool(param)]
+        #[schemars(with = "CollectionName")]
+        nft: String,
+    ) -> Result<CallToolResult, Error> {
+        let schema = match contract_family(&self.schemas, &nft, ContractRole::Minter)
+            .and_then(|family| family.query_schema())
+        {
+            Ok(schema) => schema,
+            Err(err) => return Ok(err.into()),
+        };
+        let serialized: String = serde_json::to_string(&schema).unwrap_or("".to_string());
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = BUILD_MINTER_QUERY_MSG_DESCR)]
+    async fn build_minter_query_msg(
+        &self,
+        #[tool(param)]
+        #[schemars(with = "CollectionName")]
+        nft: String,
+        #[tool(param)]
+        #[schemars(description = "contract address of minter contract")]
+        contract_addr: String,
+        #[tool(param)]
+        #[schemars(
+            description = "JSON stringified QueryMsg variant needed for building the query as a Cosmos SDK QueryRequest"
+        )]
+        query_msg: String,
+    ) -> Result<CallToolResult, Error> {
+        let valid_query = match self
+            .query_request(ContractRole::Minter, Some(&nft), contract_addr, &query_msg)
+            .and_then(|(query_request, contract)| {
+                validated_query(query_msg, &query_request, contract)
+            }) {
+            Ok(valid_query) => valid_query,
+            Err(err) => return Ok(err.into()),
+        };
+        let serialized: String = serde_json::to_string(&valid_query).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = EXECUTE_MINTER_QUERY_DESCR)]
+    async fn execute_minter_query(
+        &self,
+        #[tool(param)]
+        #[schemars(with = "CollectionName")]
+        nft: String,
+        #[tool(param)]
+        #[schemars(description = "contract address of minter contract")]
+        contract_addr: String,
+        #[tool(param)]
+        #[schemars(description = "JSON stringified QueryMsg variant to be queried")]
+        query_msg: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network to query (only required if the contract address isn't registered)"
+        )]
+        network: Option<ArchwayNetwork>,
+    ) -> Result<CallToolResult, Error> {
+        let result = match self
+            .query(
+                ContractRole::Minter,
+                Some(&nft),
+                contract_addr,
+                query_msg,
+                network,
+            )
+            .await
+        {
+            Ok(result) => result,
+            Err(err) => return Ok(err.into()),
+        };
+        let serialized: String = serde_json::to_string(&result).unwrap_or_default();
         Ok(CallToolResult::success(vec![Content::text(serialized)]))
     }
 
-    // Execute entry point tools
-    #[tool(description = LIST_TX_ENTRY_POINTS_DESCR)]
-    async fn list_tx_entry_points(&self) -> Result<CallToolResult, Error> {
-        let schema = schema_for!(ExecuteMsg);
+    // Minter Execute entry point tools
+    #[tool(description = LIST_MINTER_TX_ENTRY_POINTS_DESCR)]
+    async fn list_minter_tx_entry_points(
+        &self,
+        #[tool(param)]
+        #[schemars(with = "CollectionName")]
+        nft: String,
+    ) -> Result<CallToolResult, Error> {
+        let schema = match contract_family(&self.schemas, &nft, ContractRole::Minter)
+            .and_then(|family| family.execute_schema())
+        {
+            Ok(schema) => schema,
+            Err(err) => return Ok(err.into()),
+        };
         let serialized: String = serde_json::to_string(&schema).unwrap_or("".to_string());
         Ok(CallToolResult::success(vec![Content::text(serialized)]))
     }
 
-    #[tool(description = BUILD_EXECUTE_MSG_DESCR)]
-    async fn build_execute_msg(
+    #[tool(description = BUILD_MINTER_EXECUTE_MSG_DESCR)]
+    async fn build_minter_execute_msg(
         &self,
         #[tool(param)]
-        #[schemars(
-            description = "contract address of Ambur marketplace (e.g. mainnet or testnet address)"
-        )]
+        #[schemars(with = "CollectionName")]
+        nft: String,
+        #[tool(param)]
+        #[schemars(description = "contract address of minter contract")]
         contract_addr: String,
         #[tool(param)]
         #[schemars(
@@ -228,83 +1810,83 @@ impl AmburMcp {
         execute_msg: String,
         #[tool(param)]
         #[schemars(
-            description = "Optionally include native payment funds to be sent in the transaction (only required for 'finish' txs if payment_token is a native token)"
+            description = "Optionally include native payment funds to be sent in the transaction (only required for mint), in base units or as a human amount (e.g. \"8.88 USDC\")"
         )]
         payment: Option<String>,
         #[tool(param)]
         #[schemars(
-            description = "Optionally include native payment denom for funds being sent in the transaction (required for any transactions that require native denom payments; e.g. not cw20 payments)"
+            description = "Optionally include native payment denom (or registered symbol) for funds being sent in the transaction (required for base unit payments; e.g. not needed for \"8.88 USDC\", or for cw20 payments)"
         )]
         payment_denom: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally include the sender (signer) address to also encode the message as a MsgExecuteContract protobuf, wrapped in an unsigned TxBody"
+        )]
+        sender: Option<String>,
     ) -> Result<CallToolResult, Error> {
-        let funds: Vec<Coin> = if payment.is_some() && payment_denom.is_some() {
-            let funds = Coin {
-                denom: payment_denom.unwrap_or_default(),
-                amount: Uint128::from_str(payment.unwrap_or_default().as_str()).unwrap_or_default(),
-            };
-            vec![funds]
-        } else {
-            vec![]
-        };
-        let deserialized: ExecuteMsg = serde_json::from_str(execute_msg.as_str()).unwrap();
-        let cosmos_msg: CosmosMsg = WasmMsg::Execute {
+        let (cosmos_msg, contract) = match self.wrap_cosmos_msg(
+            ContractRole::Minter,
+            Some(&nft),
             contract_addr,
-            msg: to_json_binary(&deserialized).unwrap_or_default(),
-            funds,
-        }
-        .into();
-        let serialized_cosmos_msg = serde_json::to_string(&cosmos_msg);
-        if serialized_cosmos_msg.is_err() {
-            return Ok(CallToolResult::error(vec![Content::text(
-                "Error wrapping ExecuteMsg as CosmosMsg",
-            )]));
-        }
-        let valid_execute = ValidatedExecute {
-            execute_msg,
-            cosmos_msg: serialized_cosmos_msg.unwrap_or_default(),
+            &execute_msg,
+            payment,
+            payment_denom,
+        ) {
+            Ok(built) => built,
+            Err(err) => return Ok(err.into()),
         };
+        let valid_execute =
+            match validated_execute(execute_msg, cosmos_msg, contract, sender, vec![]) {
+                Ok(valid_execute) => valid_execute,
+                Err(err) => return Ok(err.into()),
+            };
         let serialized: String = serde_json::to_string(&valid_execute).unwrap_or_default();
         Ok(CallToolResult::success(vec![Content::text(serialized)]))
     }
 
-    // cw721 Query entry point tools
-    #[tool(description = LIST_TOKEN_QUERY_ENTRY_POINTS_DESCR)]
-    async fn list_token_query_entry_points(
+    // Loaded contract schema tools
+    #[tool(description = LIST_SCHEMA_CONTRACTS_DESCR)]
+    async fn list_schema_contracts(&self) -> Result<CallToolResult, Error> {
+        let summaries: Vec<ContractSchemaSummary> =
+            self.schemas.iter().map(ContractSchema::summary).collect();
+        let serialized: String = serde_json::to_string(&summaries).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = LIST_SCHEMA_ENTRY_POINTS_DESCR)]
+    async fn list_schema_entry_points(
         &self,
         #[tool(param)]
         #[schemars(
-            description = "name of the NFT collection (e.g. \"archies\", \"the foresight ticket\", \"derpies\", \"ghouls\")"
+            description = "name of a contract with a loaded schema (see 'list_schema_contracts')"
         )]
-        nft: String,
+        contract: String,
+        #[tool(param)]
+        #[schemars(
+            description = "kind of message to list the entry points of (\"instantiate\", \"execute\", \"query\", \"migrate\" or \"sudo\")"
+        )]
+        kind: MsgKind,
     ) -> Result<CallToolResult, Error> {
-        let schema = match nft.to_lowercase().as_str() {
-            "archies" => Some(schema_for!(ArchiesQueryMsg<ArchiesExtension>)),
-            "derpies" => Some(schema_for!(DerpiesQueryMsg<DerpiesExtension>)),
-            "ghouls" => Some(schema_for!(GhoulsQueryMsg<GhoulsExtension>)),
-            "foresight" | "the foresight ticket" => {
-                Some(schema_for!(ForesightQueryMsg<ForesightExtension>))
-            }
-            _ => None,
+        let schema = match find_schema(&self.schemas, &contract)
+            .and_then(|schema| schema.required_msg_schema(kind))
+        {
+            Ok(schema) => schema,
+            Err(err) => return Ok(err.into()),
         };
-        if schema.is_none() {
-            let err_msg = "Unrecognized NFT collection name ".to_string() + &nft;
-            return Ok(CallToolResult::error(vec![Content::text(&err_msg)]));
-        }
-        let schema = schema.unwrap();
-        let serialized: String = serde_json::to_string(&schema).unwrap_or("".to_string());
+        let serialized: String = serde_json::to_string(schema).unwrap_or_default();
         Ok(CallToolResult::success(vec![Content::text(serialized)]))
     }
 
-    #[tool(description = BUILD_TOKEN_QUERY_MSG_DESCR)]
-    async fn build_token_query_msg(
+    #[tool(description = BUILD_SCHEMA_QUERY_MSG_DESCR)]
+    async fn build_schema_query_msg(
         &self,
         #[tool(param)]
         #[schemars(
-            description = "name of the NFT collection (e.g. \"archies\", \"the foresight ticket\", \"derpies\", \"ghouls\")"
+            description = "name of a contract with a loaded schema (see 'list_schema_contracts')"
         )]
-        nft: String,
+        contract: String,
         #[tool(param)]
-        #[schemars(description = "contract address of cw721 token")]
+        #[schemars(description = "address of the contract to query")]
         contract_addr: String,
         #[tool(param)]
         #[schemars(
@@ -312,417 +1894,842 @@ impl AmburMcp {
         )]
         query_msg: String,
     ) -> Result<CallToolResult, Error> {
-        let query_req: Option<QueryRequest> = match nft.to_lowercase().as_str() {
-            "archies" => {
-                let deserialized: ArchiesQueryMsg<ArchiesExtension> =
-                    serde_json::from_str(query_msg.as_str()).unwrap();
-                let query_req = QueryRequest::Wasm(WasmQuery::Smart {
-                    contract_addr,
-                    msg: to_json_binary(&deserialized).unwrap_or_default(),
-                });
-                Some(query_req)
-            }
-            "derpies" => {
-                let deserialized: DerpiesQueryMsg<DerpiesExtension> =
-                    serde_json::from_str(query_msg.as_str()).unwrap();
-                let query_req = QueryRequest::Wasm(WasmQuery::Smart {
-                    contract_addr,
-                    msg: to_json_binary(&deserialized).unwrap_or_default(),
-                });
-                Some(query_req)
-            }
-            "ghouls" => {
-                let deserialized: GhoulsQueryMsg<GhoulsExtension> =
-                    serde_json::from_str(query_msg.as_str()).unwrap();
-                let query_req = QueryRequest::Wasm(WasmQuery::Smart {
-                    contract_addr,
-                    msg: to_json_binary(&deserialized).unwrap_or_default(),
-                });
-                Some(query_req)
-            }
-            "foresight" | "the foresight ticket" => {
-                let deserialized: ForesightQueryMsg<ForesightExtension> =
-                    serde_json::from_str(query_msg.as_str()).unwrap();
-                let query_req = QueryRequest::Wasm(WasmQuery::Smart {
-                    contract_addr,
-                    msg: to_json_binary(&deserialized).unwrap_or_default(),
-                });
-                Some(query_req)
-            }
-            _ => None,
-        };
-        if query_req.is_none() {
-            return Ok(CallToolResult::error(vec![Content::text(
-                "Error deserializing token QueryMsg",
-            )]));
-        }
-        let serialized_query_req = serde_json::to_string(&query_req);
-        if serialized_query_req.is_err() {
-            return Ok(CallToolResult::error(vec![Content::text(
-                "Error wrapping token QueryMsg as QueryRequest",
-            )]));
-        }
-        let valid_query = ValidatedQuery {
-            query_msg,
-            query_request: serialized_query_req.unwrap_or_default(),
+        let valid_query = match self
+            .schema_query_request(&contract, contract_addr, &query_msg)
+            .and_then(|(query_request, contract)| {
+                validated_query(query_msg, &query_request, contract)
+            }) {
+            Ok(valid_query) => valid_query,
+            Err(err) => return Ok(err.into()),
         };
         let serialized: String = serde_json::to_string(&valid_query).unwrap_or_default();
         Ok(CallToolResult::success(vec![Content::text(serialized)]))
     }
 
-    // cw721 Execute entry point tools
-    #[tool(description = LIST_TOKEN_TX_ENTRY_POINTS_DESCR)]
-    async fn list_token_tx_entry_points(
+    #[tool(description = EXECUTE_SCHEMA_QUERY_DESCR)]
+    async fn execute_schema_query(
         &self,
         #[tool(param)]
         #[schemars(
-            description = "name of the NFT collection (e.g. \"archies\", \"the foresight ticket\", \"derpies\", \"ghouls\")"
+            description = "name of a contract with a loaded schema (see 'list_schema_contracts')"
         )]
-        nft: String,
+        contract: String,
+        #[tool(param)]
+        #[schemars(description = "address of the contract to query")]
+        contract_addr: String,
+        #[tool(param)]
+        #[schemars(description = "JSON stringified QueryMsg variant to be queried")]
+        query_msg: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally the network to query (only required if the contract address isn't registered)"
+        )]
+        network: Option<ArchwayNetwork>,
     ) -> Result<CallToolResult, Error> {
-        let schema = match nft.to_lowercase().as_str() {
-            "archies" => Some(schema_for!(ArchiesExecuteMsg)),
-            "derpies" => Some(schema_for!(DerpiesExecuteMsg)),
-            "ghouls" => Some(schema_for!(GhoulsExecuteMsg)),
-            "foresight" | "the foresight ticket" => Some(schema_for!(ForesightExecuteMsg)),
-            _ => None,
+        let result = match self
+            .schema_query(&contract, contract_addr, query_msg, network)
+            .await
+        {
+            Ok(result) => result,
+            Err(err) => return Ok(err.into()),
         };
-        if schema.is_none() {
-            let err_msg = "Unrecognized NFT collection name ".to_string() + &nft;
-            return Ok(CallToolResult::error(vec![Content::text(&err_msg)]));
-        }
-        let schema = schema.unwrap();
-        let serialized: String = serde_json::to_string(&schema).unwrap_or("".to_string());
+        let serialized: String = serde_json::to_string(&result).unwrap_or_default();
         Ok(CallToolResult::success(vec![Content::text(serialized)]))
     }
 
-    #[tool(description = BUILD_TOKEN_EXECUTE_MSG_DESCR)]
-    async fn build_token_execute_msg(
+    #[tool(description = BUILD_SCHEMA_EXECUTE_MSG_DESCR)]
+    async fn build_schema_execute_msg(
         &self,
         #[tool(param)]
         #[schemars(
-            description = "name of the NFT collection (e.g. \"archies\", \"the foresight ticket\", \"derpies\", \"ghouls\")"
+            description = "name of a contract with a loaded schema (see 'list_schema_contracts')"
         )]
-        nft: String,
+        contract: String,
         #[tool(param)]
-        #[schemars(description = "contract address of cw721 token")]
+        #[schemars(description = "address of the contract to execute")]
         contract_addr: String,
         #[tool(param)]
         #[schemars(
             description = "ExecuteMsg variant and its values needed for building the transaction as a Cosmos SDK CosmosMsg"
         )]
         execute_msg: String,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally include native payment funds to be sent in the transaction, in base units or as a human amount (e.g. \"8.88 USDC\")"
+        )]
+        payment: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally include native payment denom (or registered symbol) for funds being sent in the transaction (required for base unit payments; e.g. not needed for \"8.88 USDC\")"
+        )]
+        payment_denom: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally include the sender (signer) address to also encode the message as a MsgExecuteContract protobuf, wrapped in an unsigned TxBody"
+        )]
+        sender: Option<String>,
     ) -> Result<CallToolResult, Error> {
-        let cosmos_msg: Option<CosmosMsg> = match nft.to_lowercase().as_str() {
-            "archies" => {
-                let deserialized: ArchiesExecuteMsg =
-                    serde_json::from_str(execute_msg.as_str()).unwrap();
-                let cosmos_msg: CosmosMsg = WasmMsg::Execute {
-                    contract_addr,
-                    msg: to_json_binary(&deserialized).unwrap_or_default(),
-                    funds: vec![],
-                }
-                .into();
-                Some(cosmos_msg)
-            }
-            "derpies" => {
-                let deserialized: DerpiesExecuteMsg =
-                    serde_json::from_str(execute_msg.as_str()).unwrap();
-                let cosmos_msg: CosmosMsg = WasmMsg::Execute {
-                    contract_addr,
-                    msg: to_json_binary(&deserialized).unwrap_or_default(),
-                    funds: vec![],
-                }
-                .into();
-                Some(cosmos_msg)
-            }
-            "ghouls" => {
-                let deserialized: GhoulsExecuteMsg =
-                    serde_json::from_str(execute_msg.as_str()).unwrap();
-                let cosmos_msg: CosmosMsg = WasmMsg::Execute {
-                    contract_addr,
-                    msg: to_json_binary(&deserialized).unwrap_or_default(),
-                    funds: vec![],
-                }
-                .into();
-                Some(cosmos_msg)
-            }
-            "foresight" | "the foresight ticket" => {
-                let deserialized: ForesightExecuteMsg =
-                    serde_json::from_str(execute_msg.as_str()).unwrap();
-                let cosmos_msg: CosmosMsg = WasmMsg::Execute {
-                    contract_addr,
-                    msg: to_json_binary(&deserialized).unwrap_or_default(),
-                    funds: vec![],
-                }
-                .into();
-                Some(cosmos_msg)
-            }
-            _ => None,
-        };
-        if cosmos_msg.is_none() {
-            return Ok(CallToolResult::error(vec![Content::text(
-                "Error deserializing execute_msg to ExecuteMsg",
-            )]));
-        }
-        let serialized_cosmos_msg = serde_json::to_string(&cosmos_msg);
-        if serialized_cosmos_msg.is_err() {
-            return Ok(CallToolResult::error(vec![Content::text(
-                "Error wrapping ExecuteMsg as CosmosMsg",
-            )]));
-        }
-        let valid_execute = ValidatedExecute {
-            execute_msg,
-            cosmos_msg: serialized_cosmos_msg.unwrap_or_default(),
+        let (cosmos_msg, contract) = match self.schema_cosmos_msg(
+            &contract,
+            contract_addr,
+            &execute_msg,
+            payment,
+            payment_denom,
+        ) {
+            Ok(built) => built,
+            Err(err) => return Ok(err.into()),
         };
+        let valid_execute =
+            match validated_execute(execute_msg, cosmos_msg, contract, sender, vec![]) {
+                Ok(valid_execute) => valid_execute,
+                Err(err) => return Ok(err.into()),
+            };
         let serialized: String = serde_json::to_string(&valid_execute).unwrap_or_default();
         Ok(CallToolResult::success(vec![Content::text(serialized)]))
     }
 
-    // Minter Query entry point tools
-    #[tool(description = LIST_MINTER_QUERY_ENTRY_POINTS_DESCR)]
-    async fn list_minter_query_entry_points(
+    // Marketplace workflow tools
+    #[tool(description = PREPARE_SALE_LISTING_DESCR)]
+    async fn prepare_sale_listing(
         &self,
         #[tool(param)]
+        #[schemars(with = "CollectionName")]
+        nft: String,
+        #[tool(param)]
+        #[schemars(description = "token id of the NFT to list for sale")]
+        token_id: String,
+        #[tool(param)]
         #[schemars(
-            description = "name of the NFT collection (e.g. \"archies\", \"the foresight ticket\", \"derpies\", \"ghouls\")"
+            description = "sale price in USDC (e.g. \"12.5 USDC\"); whole numbers need their symbol or a decimal point (e.g. \"12 USDC\" or \"12.0\")"
         )]
-        nft: String,
-    ) -> Result<CallToolResult, Error> {
-        let schema = match nft.to_lowercase().as_str() {
-            "archies" => Some(schema_for!(ArchiesMinterQueryMsg)),
-            "derpies" => Some(schema_for!(DerpiesMinterQueryMsg)),
-            "ghouls" => Some(schema_for!(GhoulsMinterQueryMsg)),
-            "foresight" | "the foresight ticket" => Some(schema_for!(ForesightMinterQueryMsg)),
-            _ => None,
-        };
-        if schema.is_none() {
-            let err_msg = "Unrecognized NFT collection name ".to_string() + &nft;
-            return Ok(CallToolResult::error(vec![Content::text(&err_msg)]));
-        }
-        let schema = schema.unwrap();
-        let serialized: String = serde_json::to_string(&schema).unwrap_or("".to_string());
-        Ok(CallToolResult::success(vec![Content::text(serialized)]))
-    }
-
-    #[tool(description = BUILD_MINTER_QUERY_MSG_DESCR)]
-    async fn build_minter_query_msg(
-        &self,
+        price: String,
         #[tool(param)]
         #[schemars(
-            description = "name of the NFT collection (e.g. \"archies\", \"the foresight ticket\", \"derpies\", \"ghouls\")"
+            description = "Optionally include when the listing expires (e.g. \"in 7 days\", \"2026-12-31T18:00:00Z\", \"height 1234567\" or a JSON Expiration, see 'build_expiration'), defaults to never expiring"
         )]
-        nft: String,
+        expires: Option<String>,
         #[tool(param)]
-        #[schemars(description = "contract address of minter contract")]
-        contract_addr: String,
+        #[schemars(description = "network to list the NFT on (e.g. \"Mainnet\")")]
+        network: ArchwayNetwork,
         #[tool(param)]
         #[schemars(
-            description = "JSON stringified QueryMsg variant needed for building the query as a Cosmos SDK QueryRequest"
+            description = "Optionally include the seller (signer) address to also encode the messages as an unsigned TxBody"
         )]
-        query_msg: String,
+        sender: Option<String>,
     ) -> Result<CallToolResult, Error> {
-        let query_req: Option<QueryRequest> = match nft.to_lowercase().as_str() {
-            "archies" => {
-                let deserialized: ArchiesMinterQueryMsg =
-                    serde_json::from_str(query_msg.as_str()).unwrap();
-                let query_req = QueryRequest::Wasm(WasmQuery::Smart {
-                    contract_addr,
-                    msg: to_json_binary(&deserialized).unwrap_or_default(),
-                });
-                Some(query_req)
-            }
-            "derpies" => {
-                let deserialized: DerpiesMinterQueryMsg =
-                    serde_json::from_str(query_msg.as_str()).unwrap();
-                let query_req = QueryRequest::Wasm(WasmQuery::Smart {
-                    contract_addr,
-                    msg: to_json_binary(&deserialized).unwrap_or_default(),
-                });
-                Some(query_req)
-            }
-            "ghouls" => {
-                let deserialized: GhoulsMinterQueryMsg =
-                    serde_json::from_str(query_msg.as_str()).unwrap();
-                let query_req = QueryRequest::Wasm(WasmQuery::Smart {
-                    contract_addr,
-                    msg: to_json_binary(&deserialized).unwrap_or_default(),
-                });
-                Some(query_req)
-            }
-            "foresight" | "the foresight ticket" => {
-                let deserialized: ForesightMinterQueryMsg =
-                    serde_json::from_str(query_msg.as_str()).unwrap();
-                let query_req = QueryRequest::Wasm(WasmQuery::Smart {
-                    contract_addr,
-                    msg: to_json_binary(&deserialized).unwrap_or_default(),
-                });
-                Some(query_req)
-            }
-            _ => None,
-        };
-        if query_req.is_none() {
-            return Ok(CallToolResult::error(vec![Content::text(
-                "Error deserializing token QueryMsg",
-            )]));
-        }
-        let serialized_query_req = serde_json::to_string(&query_req);
-        if serialized_query_req.is_err() {
-            return Ok(CallToolResult::error(vec![Content::text(
-                "Error wrapping token QueryMsg as QueryRequest",
-            )]));
-        }
-        let valid_query = ValidatedQuery {
-            query_msg,
-            query_request: serialized_query_req.unwrap_or_default(),
+        let prepared = match self
+            .sale_listing(&nft, token_id, &price, expires, network, sender)
+            .await
+        {
+            Ok(prepared) => prepared,
+            Err(err) => return Ok(err.into()),
         };
-        let serialized: String = serde_json::to_string(&valid_query).unwrap_or_default();
+        let serialized: String = serde_json::to_string(&prepared).unwrap_or_default();
         Ok(CallToolResult::success(vec![Content::text(serialized)]))
     }
 
-    // Minter Execute entry point tools
-    #[tool(description = LIST_MINTER_TX_ENTRY_POINTS_DESCR)]
-    async fn list_minter_tx_entry_points(
+    #[tool(description = PREPARE_PURCHASE_DESCR)]
+    async fn prepare_purchase(
         &self,
         #[tool(param)]
+        #[schemars(description = "id of the Sale swap to purchase")]
+        swap_id: String,
+        #[tool(param)]
+        #[schemars(description = "network the swap was created on (e.g. \"Mainnet\")")]
+        network: ArchwayNetwork,
+        #[tool(param)]
         #[schemars(
-            description = "name of the NFT collection (e.g. \"archies\", \"the foresight ticket\", \"derpies\", \"ghouls\")"
+            description = "Optionally include the swap's 'details' query response (as JSON, base64 encoded JSON, or the LCD response), it's queried from the network when omitted (requires live queries to be enabled)"
         )]
-        nft: String,
+        details: Option<String>,
+        #[tool(param)]
+        #[schemars(
+            description = "Optionally include the buyer (signer) address to also encode the messages as an unsigned TxBody"
+        )]
+        sender: Option<String>,
     ) -> Result<CallToolResult, Error> {
-        let schema = match nft.to_lowercase().as_str() {
-            "archies" => Some(schema_for!(ArchiesMinterExecuteMsg)),
-            "derpies" => Some(schema_for!(DerpiesMinterExecuteMsg)),
-            "ghouls" => Some(schema_for!(GhoulsMinterExecuteMsg)),
-            "foresight" | "the foresight ticket" => Some(schema_for!(ForesightMinterExecuteMsg)),
-            _ => None,
+        let prepared = match self.purchase(&swap_id, network, details, sender).await {
+            Ok(prepared) => prepared,
+            Err(err) => return Ok(err.into()),
         };
-        if schema.is_none() {
-            let err_msg = "Unrecognized NFT collection name ".to_string() + &nft;
-            return Ok(CallToolResult::error(vec![Content::text(&err_msg)]));
-        }
-        let schema = schema.unwrap();
-        let serialized: String = serde_json::to_string(&schema).unwrap_or("".to_string());
+        let serialized: String = serde_json::to_string(&prepared).unwrap_or_default();
         Ok(CallToolResult::success(vec![Content::text(serialized)]))
     }
 
-    #[tool(description = BUILD_MINTER_EXECUTE_MSG_DESCR)]
-    async fn build_minter_execute_msg(
+    #[tool(description = PREPARE_OFFER_DESCR)]
+    async fn prepare_offer(
         &self,
         #[tool(param)]
-        #[schemars(
-            description = "name of the NFT collection (e.g. \"archies\", \"the foresight ticket\", \"derpies\", \"ghouls\")"
-        )]
+        #[schemars(with = "CollectionName")]
         nft: String,
         #[tool(param)]
-        #[schemars(description = "contract address of minter contract")]
-        contract_addr: String,
+        #[schemars(description = "token id of the NFT to make an offer for")]
+        token_id: String,
         #[tool(param)]
         #[schemars(
-            description = "ExecuteMsg variant and its values needed for building the transaction as a Cosmos SDK CosmosMsg"
+            description = "offered price in wUSDC (e.g. \"12.5 wUSDC\"); whole numbers need their symbol or a decimal point (e.g. \"12 wUSDC\" or \"12.0\")"
         )]
-        execute_msg: String,
+        price: String,
         #[tool(param)]
         #[schemars(
-            description = "Optionally include native payment funds to be sent in the transaction (only required for mint)"
+            description = "Optionally include when the offer expires (e.g. \"in 7 days\", \"2026-12-31T18:00:00Z\", \"height 1234567\" or a JSON Expiration, see 'build_expiration'), defaults to never expiring"
         )]
-        payment: Option<String>,
+        expires: Option<String>,
+        #[tool(param)]
+        #[schemars(description = "network to make the offer on (e.g. \"Mainnet\")")]
+        network: ArchwayNetwork,
         #[tool(param)]
         #[schemars(
-            description = "Optionally include native payment denom for funds being sent in the transaction (required for any transactions that require native denom payments; e.g. not cw20 payments)"
+            description = "Optionally include the offerer (signer) address to also encode the messages as an unsigned TxBody"
         )]
-        payment_denom: Option<String>,
+        sender: Option<String>,
     ) -> Result<CallToolResult, Error> {
-        let funds: Vec<Coin> = if payment.is_some() && payment_denom.is_some() {
-            let funds = Coin {
-                denom: payment_denom.unwrap_or_default(),
-                amount: Uint128::from_str(payment.unwrap_or_default().as_str()).unwrap_or_default(),
-            };
-            vec![funds]
-        } else {
-            vec![]
-        };
-        let cosmos_msg: Option<CosmosMsg> = match nft.to_lowercase().as_str() {
-            "archies" => {
-                let deserialized: ArchiesMinterExecuteMsg =
-                    serde_json::from_str(execute_msg.as_str()).unwrap();
-                let cosmos_msg: CosmosMsg = WasmMsg::Execute {
-                    contract_addr,
-                    msg: to_json_binary(&deserialized).unwrap_or_default(),
-                    funds,
-                }
-                .into();
-                Some(cosmos_msg)
-            }
-            "derpies" => {
-                let deserialized: DerpiesMinterExecuteMsg =
-                    serde_json::from_str(execute_msg.as_str()).unwrap();
-                let cosmos_msg: CosmosMsg = WasmMsg::Execute {
-                    contract_addr,
-                    msg: to_json_binary(&deserialized).unwrap_or_default(),
-                    funds,
-                }
-                .into();
-                Some(cosmos_msg)
-            }
-            "ghouls" => {
-                let deserialized: GhoulsMinterExecuteMsg =
-                    serde_json::from_str(execute_msg.as_str()).unwrap();
-                let cosmos_msg: CosmosMsg = WasmMsg::Execute {
-                    contract_addr,
-                    msg: to_json_binary(&deserialized).unwrap_or_default(),
-                    funds,
-                }
-                .into();
-                Some(cosmos_msg)
-            }
-            "foresight" | "the foresight ticket" => {
-                let deserialized: ForesightMinterExecuteMsg =
-                    serde_json::from_str(execute_msg.as_str()).unwrap();
-                let cosmos_msg: CosmosMsg = WasmMsg::Execute {
-                    contract_addr,
-                    msg: to_json_binary(&deserialized).unwrap_or_default(),
-                    funds,
-                }
-                .into();
-                Some(cosmos_msg)
-            }
-            _ => None,
+        let prepared = match self
+            .offer(&nft, token_id, &price, expires, network, sender)
+            .await
+        {
+            Ok(prepared) => prepared,
+            Err(err) => return Ok(err.into()),
         };
-        if cosmos_msg.is_none() {
-            return Ok(CallToolResult::error(vec![Content::text(
-                "Error deserializing execute_msg to ExecuteMsg",
-            )]));
-        }
-        let serialized_cosmos_msg = serde_json::to_string(&cosmos_msg);
-        if serialized_cosmos_msg.is_err() {
-            return Ok(CallToolResult::error(vec![Content::text(
-                "Error wrapping ExecuteMsg as CosmosMsg",
-            )]));
-        }
-        let valid_execute = ValidatedExecute {
-            execute_msg,
-            cosmos_msg: serialized_cosmos_msg.unwrap_or_default(),
+        let serialized: String = serde_json::to_string(&prepared).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(serialized)]))
+    }
+
+    #[tool(description = PREPARE_COLLECTION_OFFER_DESCR)]
+    async fn prepare_collection_offer(
+        &self,
+        #[tool(aggr)] request: CollectionOfferRequest,
+    ) -> Result<CallToolResult, Error> {
+        let prepared = match self.collection_offer(request).await {
+            Ok(prepared) => prepared,
+            Err(err) => return Ok(err.into()),
         };
-        let serialized: String = serde_json::to_string(&valid_execute).unwrap_or_default();
+        let serialized: String = serde_json::to_string(&prepared).unwrap_or_default();
         Ok(CallToolResult::success(vec![Content::text(serialized)]))
     }
 }
 
+// The marketplace's error for an unknown swap id: cosmwasm's StdError::NotFound
+// for its CW721Swap storage (e.g. "type: cw721_marketplace::state::CW721Swap;
+// key: [..] not found"), as relayed in the LCD's error body
+fn is_swap_not_found(body: &LcdErrorBody) -> bool {
+    body.message.contains("CW721Swap") && body.message.contains("not found")
+}
+
+// The wUSDC allowance followed by the marketplace message of an offer
+fn wusdc_offer_entries(wusdc: &str, marketplace: &str, execute_msgs: &[Value]) -> Vec<BundleEntry> {
+    vec![
+        BundleEntry {
+            contract_kind: ContractRole::Cw20,
+            nft: None,
+            contract_addr: wusdc.to_string(),
+            execute_msg: execute_msgs[0].to_string(),
+            payment: None,
+            payment_denom: None,
+        },
+        BundleEntry {
+            contract_kind: ContractRole::Marketplace,
+            nft: None,
+            contract_addr: marketplace.to_string(),
+            execute_msg: execute_msgs[1].to_string(),
+            payment: None,
+            payment_denom: None,
+        },
+    ]
+}
+
+// The QueryMsg or ExecuteMsg schema of a contract, as JSON
+fn entry_point_schema(
+    schemas: &[ContractSchema],
+    role: ContractRole,
+    nft: Option<&str>,
+    kind: MsgKind,
+) -> Result<Value, AmburError> {
+    let collection = || {
+        nft.ok_or_else(|| {
+            AmburError::new(
+                ErrorCode::UnknownCollection,
+                format!("An NFT collection name is required for {role:?} entry points"),
+            )
+        })
+    };
+    let schema = match (role, kind) {
+        (ContractRole::Marketplace, MsgKind::Query) => schema_for!(QueryMsg),
+        (ContractRole::Marketplace, MsgKind::Execute) => schema_for!(ExecuteMsg),
+        (ContractRole::Token | ContractRole::Minter, MsgKind::Query) => {
+            return contract_family(schemas, collection()?, role)?.query_schema();
+        }
+        (ContractRole::Token | ContractRole::Minter, MsgKind::Execute) => {
+            return contract_family(schemas, collection()?, role)?.execute_schema();
+        }
+        (ContractRole::Cw20, MsgKind::Query) => schema_for!(Cw20QueryMsg),
+        (ContractRole::Cw20, MsgKind::Execute) => schema_for!(Cw20ExecuteMsg),
+        (role, kind) => {
+            return Err(AmburError::new(
+                ErrorCode::InvalidMessage,
+                format!(
+                    "{role:?} contracts only have query and execute entry points, not {kind:?}"
+                ),
+            ));
+        }
+    };
+    serde_json::to_value(&schema).map_err(|err| AmburError::serialization::<Value>(err.to_string()))
+}
+
+fn validated_query(
+    query_msg: String,
+    query_request: &QueryRequest,
+    contract: ContractMatch,
+) -> Result<ValidatedQuery, AmburError> {
+    let serialized_query_request = serde_json::to_string(query_request).map_err(|_| {
+        AmburError::serialization::<QueryRequest>("Error wrapping QueryMsg as QueryRequest")
+    })?;
+    Ok(ValidatedQuery {
+        query_msg,
+        query_request: serialized_query_request,
+        contract,
+    })
+}
+
+fn validated_execute(
+    execute_msg: String,
+    cosmos_msg: CosmosMsg,
+    contract: ContractMatch,
+    sender: Option<String>,
+    warnings: Vec<String>,
+) -> Result<ValidatedExecute, AmburError> {
+    let serialized_cosmos_msg = serde_json::to_string(&cosmos_msg).map_err(|_| {
+        AmburError::serialization::<CosmosMsg>("Error wrapping ExecuteMsg as CosmosMsg")
+    })?;
+    let tx_body = sender
+        .map(|sender| build_tx_body(&sender, std::slice::from_ref(&cosmos_msg), "", 0))
+        .transpose()?;
+    Ok(ValidatedExecute {
+        execute_msg,
+        cosmos_msg: serialized_cosmos_msg,
+        contract,
+        tx_body,
+        warnings,
+    })
+}
+
 impl Default for AmburMcp {
     fn default() -> Self {
         Self::new()
     }
 }
 
-#[tool(tool_box)]
+// The tool box's list_tools and call_tool, leaving sign_tx out unless signing is
+// enabled
 impl ServerHandler for AmburMcp {
+    async fn list_tools(
+        &self,
+        _request: PaginatedRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, Error> {
+        let tools = Self::tool_box()
+            .list()
+            .into_iter()
+            .filter(|tool| self.signing_enabled() || tool.name != SIGN_TX_TOOL)
+            .collect();
+        Ok(ListToolsResult {
+            next_cursor: None,
+            tools,
+        })
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, Error> {
+        if request.name == SIGN_TX_TOOL && !self.signing_enabled() {
+            return Err(Error::invalid_params("tool not found", None));
+        }
+        let context = ToolCallContext::new(self, request, context);
+        Self::tool_box().call(context).await
+    }
+
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
             protocol_version: ProtocolVersion::V_2024_11_05,
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_resources()
+                .enable_prompts()
+                .build(),
             server_info: Implementation::from_build_env(),
             instructions: Some(SERVER_INFO_DESCR.to_string()),
         }
     }
+
+    async fn list_resources(
+        &self,
+        _request: PaginatedRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, Error> {
+        Ok(ListResourcesResult {
+            resources: list_resources(&self.registry, &self.schemas),
+            next_cursor: None,
+        })
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: PaginatedRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, Error> {
+        Ok(ListResourceTemplatesResult {
+            resource_templates: list_resource_templates(),
+            next_cursor: None,
+        })
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, Error> {
+        let content = read_resource(&request.uri, &self.registry, &self.schemas)?;
+        let serialized: String = serde_json::to_string(&content).unwrap_or_default();
+        Ok(ReadResourceResult {
+            contents: vec![ResourceContents::TextResourceContents {
+                uri: request.uri,
+                mime_type: Some(RESOURCE_MIME_TYPE.to_string()),
+                text: serialized,
+            }],
+        })
+    }
+
+    async fn list_prompts(
+        &self,
+        _request: PaginatedRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, Error> {
+        Ok(ListPromptsResult {
+            prompts: list_prompts(&self.schemas),
+            next_cursor: None,
+        })
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, Error> {
+        Ok(get_prompt(
+            &request.name,
+            request.arguments,
+            &self.registry,
+            &self.schemas,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{TOKEN_ARCHIES_MAINNET, WUSDC_MAINNET};
+    use rmcp::model::PromptMessageContent;
+    use wiremock::matchers::{method, path_regex};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    // A server whose Mainnet LCD is `lcd`, with live queries on
+    fn with_lcd(lcd: &str) -> AmburMcp {
+        let mut registry = Registry::default();
+        for config in &mut registry.networks {
+            if config.network == ArchwayNetwork::Mainnet {
+                config.lcd = Some(lcd.to_string());
+            }
+        }
+        AmburMcp::with_registry(registry).with_live_queries(true)
+    }
+
+    async fn lcd_responding(data: Value) -> MockServer {
+        lcd_with(ResponseTemplate::new(200).set_body_json(json!({ "data": data }))).await
+    }
+
+    async fn lcd_with(response: ResponseTemplate) -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path_regex("^/cosmwasm/wasm/v1/contract/[a-z0-9]+/smart/"))
+            .respond_with(response)
+            .mount(&server)
+            .await;
+        server
+    }
+
+    // Every tool argument the prompts tell the model to pass exists on its tool
+    #[test]
+    fn prompts_reference_existing_tool_arguments() {
+        use crate::cw721::{Cw721ExecuteMsg, Cw721QueryMsg};
+        use crate::family::COLLECTION_FAMILIES;
+
+        let schemas: Vec<ContractSchema> = COLLECTION_FAMILIES
+            .iter()
+            .map(|family| {
+                serde_json::from_value(json!({
+                    "contract_name": family.minter_schema,
+                    "query": schema_for!(Cw721QueryMsg),
+                    "execute": schema_for!(Cw721ExecuteMsg),
+                }))
+                .unwrap()
+            })
+            .collect();
+        let tools = AmburMcp::tool_box().list();
+        // Tools each prompt names, and the arguments it tells the model to pass them
+        type References = &'static [(&'static str, &'static [&'static str])];
+        let prompts: [(&str, Value, References); 4] = [
+            (
+                "list-nft-for-sale",
+                json!({ "nft": "archies", "token_id": "1", "price": "12.5 USDC", "expires": "in 7 days" }),
+                &[
+                    (
+                        "execute_token_query",
+                        &["nft", "contract_addr", "query_msg"],
+                    ),
+                    (
+                        "prepare_sale_listing",
+                        &["nft", "token_id", "price", "network", "expires"],
+                    ),
+                    ("build_sign_doc", &["network", "sender", "cosmos_msgs"]),
+                ],
+            ),
+            (
+                "make-collection-offer",
+                json!({ "nft": "derpies", "quantity": "2", "price_per_nft": "10 wUSDC", "sender": "archway1sender" }),
+                &[
+                    (
+                        "prepare_collection_offer",
+                        &["nft", "quantity", "price_per_nft", "network", "sender"],
+                    ),
+                    ("build_sign_doc", &["network", "sender", "cosmos_msgs"]),
+                ],
+            ),
+            (
+                "mint-from-minter",
+                json!({ "nft": "ghouls" }),
+                &[
+                    ("list_minter_query_entry_points", &["nft"]),
+                    ("execute_minter_query", &["nft", "contract_addr"]),
+                    ("list_minter_tx_entry_points", &["nft"]),
+                    (
+                        "build_minter_execute_msg",
+                        &[
+                            "nft",
+                            "contract_addr",
+                            "execute_msg",
+                            "payment",
+                            "payment_denom",
+                        ],
+                    ),
+                    ("build_sign_doc", &["network", "sender", "cosmos_msgs"]),
+                ],
+            ),
+            (
+                "redeem-foresight-ticket",
+                json!({ "token_id": "7", "sender": "archway1sender" }),
+                &[
+                    (
+                        "execute_token_query",
+                        &["nft", "contract_addr", "query_msg"],
+                    ),
+                    ("list_minter_tx_entry_points", &["nft"]),
+                    (
+                        "build_minter_execute_msg",
+                        &["nft", "contract_addr", "execute_msg", "sender"],
+                    ),
+                    ("build_tx_bundle", &[]),
+                    ("build_sign_doc", &["network", "sender", "cosmos_msgs"]),
+                ],
+            ),
+        ];
+        for (prompt, arguments, references) in prompts {
+            let result = get_prompt(
+                prompt,
+                arguments.as_object().cloned(),
+                &Registry::default(),
+                &schemas,
+            )
+            .unwrap();
+            let text = match &result.messages[0].content {
+                PromptMessageContent::Text { text } => text.clone(),
+                content => panic!("{prompt} isn't text: {content:?}"),
+            };
+            assert!(!text.contains("base_units"), "{prompt}: {text}");
+            for (tool_name, params) in references {
+                assert!(
+                    text.contains(&format!("'{tool_name}'")),
+                    "{prompt}: {tool_name}"
+                );
+                let tool = tools.iter().find(|tool| tool.name == *tool_name).unwrap();
+                let properties = tool.input_schema["properties"].as_object().unwrap();
+                for param in *params {
+                    assert!(text.contains(param), "{prompt} doesn't mention {param}");
+                    assert!(
+                        properties.contains_key(*param),
+                        "{prompt}: {tool_name} has no '{param}' argument"
+                    );
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn query_validates_the_response_type() {
+        use crate::contract::TOKEN_GHOULS_MAINNET;
+
+        let owner_of = r#"{"owner_of":{"token_id":"1"}}"#;
+        let server = lcd_responding(json!({ "owner": "archway1owner", "approvals": [] })).await;
+        let result = with_lcd(&server.uri())
+            .query(
+                ContractRole::Token,
+                Some("ghouls"),
+                TOKEN_GHOULS_MAINNET.to_string(),
+                owner_of.to_string(),
+                Some(ArchwayNetwork::Mainnet),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.response_type.as_deref(), Some("OwnerOfResponse"));
+        assert_eq!(result.response["owner"], "archway1owner");
+
+        // A response that doesn't match the query's return type is rejected
+        let server = lcd_responding(json!({ "owner": 5 })).await;
+        let err = with_lcd(&server.uri())
+            .query(
+                ContractRole::Token,
+                Some("ghouls"),
+                TOKEN_GHOULS_MAINNET.to_string(),
+                owner_of.to_string(),
+                Some(ArchwayNetwork::Mainnet),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidMessage);
+    }
+
+    #[tokio::test]
+    async fn run_query_requires_live_queries() {
+        let server = lcd_responding(json!({})).await;
+        let ambur = with_lcd(&server.uri()).with_live_queries(false);
+        let contract = resolve_any_address(
+            &ambur
+                .registry
+                .marketplace(&ArchwayNetwork::Mainnet)
+                .unwrap()
+                .contract_address,
+            Some(&ArchwayNetwork::Mainnet),
+            &ambur.registry,
+        )
+        .unwrap();
+        let err = ambur
+            .run_query(&contract, None, &Binary::from(b"{}"))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::QueriesDisabled);
+    }
+
+    #[tokio::test]
+    async fn check_swap_id_only_accepts_the_marketplace_not_found_error() {
+        let not_found = json!({
+            "code": 2,
+            "message": "rpc error: code = Unknown desc = type: cw721_marketplace::state::CW721Swap; key: [00, 05, 73, 77, 61, 70, 73] not found: query wasm contract failed: unknown request",
+            "details": []
+        });
+        let server = lcd_with(ResponseTemplate::new(500).set_body_json(not_found)).await;
+        let checked = with_lcd(&server.uri())
+            .check_swap_id("unused", &ArchwayNetwork::Mainnet)
+            .await
+            .unwrap();
+        assert!(checked);
+
+        // Other errors (even ones mentioning "not found") mean the id couldn't be checked
+        for response in [
+            ResponseTemplate::new(404).set_body_string("404 page not found"),
+            ResponseTemplate::new(500).set_body_json(json!({
+                "code": 5,
+                "message": "contract archway1...: not found",
+                "details": []
+            })),
+            ResponseTemplate::new(200).set_body_json(json!({ "unexpected": true })),
+        ] {
+            let server = lcd_with(response).await;
+            let err = with_lcd(&server.uri())
+                .check_swap_id("unchecked", &ArchwayNetwork::Mainnet)
+                .await
+                .unwrap_err();
+            assert_ne!(err.code, ErrorCode::SwapIdInUse);
+            assert!(err.message.contains("couldn't be checked"));
+        }
+    }
+
+    fn listing(payment_token: Value, expires: Value) -> String {
+        json!({
+            "creator": "archway1creator",
+            "contract": TOKEN_ARCHIES_MAINNET,
+            "token_id": "427",
+            "payment_token": payment_token,
+            "price": "8880000",
+            "swap_type": "Sale",
+            "expires": expires
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn purchases_of_cw20_listings_allow_then_finish() {
+        let ambur = AmburMcp::with_registry(Registry::default());
+        let marketplace = ambur
+            .registry
+            .marketplace(&ArchwayNetwork::Mainnet)
+            .unwrap()
+            .contract_address
+            .clone();
+        let details = listing(
+            json!({ "cw20": { "address": WUSDC_MAINNET } }),
+            json!({ "never": {} }),
+        );
+        let prepared = ambur
+            .purchase("3b1f6f8e", ArchwayNetwork::Mainnet, Some(details), None)
+            .await
+            .unwrap();
+        assert_eq!(
+            prepared.execute_msgs,
+            vec![
+                json!({ "increase_allowance": { "spender": marketplace, "amount": "8880000", "expires": null } }),
+                json!({ "finish": { "id": "3b1f6f8e" } }),
+            ]
+        );
+        let contracts = &prepared.bundle.contracts;
+        assert_eq!(contracts.len(), 2);
+        assert_eq!(contracts[0].contract_address, WUSDC_MAINNET);
+        assert_eq!(contracts[1].contract_address, marketplace);
+        assert_eq!(contracts[1].role, Some(ContractRole::Marketplace));
+        let finish: Value = serde_json::from_str(&prepared.bundle.cosmos_msgs[1]).unwrap();
+        assert_eq!(finish["wasm"]["execute"]["funds"], json!([]));
+
+        // Native listings are paid with funds sent with the finish message
+        let usdc = ambur
+            .registry
+            .payment_token("USDC", Some(&ArchwayNetwork::Mainnet))
+            .unwrap()
+            .token
+            .clone();
+        let details = listing(
+            json!({ "native": { "denom": usdc.id() } }),
+            json!({ "never": {} }),
+        );
+        let prepared = ambur
+            .purchase("3b1f6f8e", ArchwayNetwork::Mainnet, Some(details), None)
+            .await
+            .unwrap();
+        assert_eq!(
+            prepared.execute_msgs,
+            vec![json!({ "finish": { "id": "3b1f6f8e" } })]
+        );
+        let finish: Value = serde_json::from_str(&prepared.bundle.cosmos_msgs[0]).unwrap();
+        assert_eq!(
+            finish["wasm"]["execute"]["funds"],
+            json!([{ "denom": usdc.id(), "amount": "8880000" }])
+        );
+    }
+
+    #[tokio::test]
+    async fn expired_or_own_listings_cant_be_purchased() {
+        let ambur = AmburMcp::with_registry(Registry::default());
+        let wusdc = json!({ "cw20": { "address": WUSDC_MAINNET } });
+        // 2001-09-09, in nanoseconds
+        let expired = listing(wusdc.clone(), json!({ "at_time": "1000000000000000000" }));
+        let err = ambur
+            .purchase("3b1f6f8e", ArchwayNetwork::Mainnet, Some(expired), None)
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidExpiration);
+        assert!(
+            err.message.contains("expired listings can't be purchased"),
+            "{}",
+            err.message
+        );
+
+        let own = listing(wusdc.clone(), json!({ "never": {} }));
+        let err = ambur
+            .purchase(
+                "3b1f6f8e",
+                ArchwayNetwork::Mainnet,
+                Some(own),
+                Some("archway1creator".to_string()),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidMessage);
+        assert!(
+            err.message.contains("created by the sender"),
+            "{}",
+            err.message
+        );
+
+        let mut offer: Value =
+            serde_json::from_str(&listing(wusdc, json!({ "never": {} }))).unwrap();
+        offer["swap_type"] = json!("Offer");
+        let err = ambur
+            .purchase(
+                "3b1f6f8e",
+                ArchwayNetwork::Mainnet,
+                Some(offer.to_string()),
+                None,
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            err.message.contains("only Sale swaps can be purchased"),
+            "{}",
+            err.message
+        );
+    }
+
+    #[tokio::test]
+    async fn offer_price_updates_are_checked_with_live_queries() {
+        let offer = json!({
+            "creator": "archway1creator",
+            "contract": TOKEN_ARCHIES_MAINNET,
+            "token_id": "427",
+            "payment_token": { "cw20": { "address": WUSDC_MAINNET } },
+            "price": "888888",
+            "swap_type": "Offer",
+            "expires": { "never": {} }
+        });
+        let update = |price: &str| {
+            json!({ "update": { "id": "3b1f6f8e", "price": price, "expires": { "never": {} } } })
+                .to_string()
+        };
+        let server = lcd_responding(offer).await;
+        let ambur = with_lcd(&server.uri());
+        let marketplace = ambur
+            .registry
+            .marketplace(&ArchwayNetwork::Mainnet)
+            .unwrap()
+            .contract_address
+            .clone();
+        let (_, warnings) = ambur
+            .prepare_marketplace_msg(&marketplace, &update("888888"))
+            .await
+            .unwrap();
+        assert!(warnings.is_empty());
+        let err = ambur
+            .prepare_marketplace_msg(&marketplace, &update("999999"))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::RuleViolation);
+        assert_eq!(err.path.as_deref(), Some("update.price"));
+
+        // Without live queries the update is built, with a warning that it wasn't checked
+        let ambur = ambur.with_live_queries(false);
+        let (_, warnings) = ambur
+            .prepare_marketplace_msg(&marketplace, &update("999999"))
+            .await
+            .unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("wasn't checked"), "{}", warnings[0]);
+    }
 }
@@ -1,12 +0,0 @@
-pub use ambur_wl_minter::msg::{
-    ExecuteMsg as ForesightMinterExecuteMsg, QueryMsg as ForesightMinterQueryMsg,
-};
-pub use derpies_minter::msg::{
-    ExecuteMsg as DerpiesMinterExecuteMsg, QueryMsg as DerpiesMinterQueryMsg,
-};
-pub use ghouls_minter::msg::{
-    ExecuteMsg as GhoulsMinterExecuteMsg, QueryMsg as GhoulsMinterQueryMsg,
-};
-pub use whitelist_minter::msg::{
-    ExecuteMsg as ArchiesMinterExecuteMsg, QueryMsg as ArchiesMinterQueryMsg,
-};
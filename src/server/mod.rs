@@ -1,3 +1 @@
 pub mod ambur;
-pub mod minter;
-pub mod token;
@@ -1,13 +0,0 @@
-pub use ambur_wl_token::{
-    ExecuteMsg as ForesightExecuteMsg, Extension as ForesightExtension,
-    QueryMsg as ForesightQueryMsg,
-};
-pub use archies_token::{
-    ExecuteMsg as ArchiesExecuteMsg, Extension as ArchiesExtension, QueryMsg as ArchiesQueryMsg,
-};
-pub use derpies_token::{
-    ExecuteMsg as DerpiesExecuteMsg, Extension as DerpiesExtension, QueryMsg as DerpiesQueryMsg,
-};
-pub use ghouls_token::{
-    ExecuteMsg as GhoulsExecuteMsg, Extension as GhoulsExtension, QueryMsg as GhoulsQueryMsg,
-};
@@ -0,0 +1,375 @@
+use base64::{Engine, engine::general_purpose::STANDARD};
+use bech32::{Bech32, Hrp};
+use bip32::{DerivationPath, XPrv};
+use bip39::Mnemonic;
+use chacha20poly1305::{
+    ChaCha20Poly1305, KeyInit, Nonce,
+    aead::{Aead, AeadCore, OsRng, rand_core::RngCore},
+};
+use cosmos_sdk_proto::{cosmos::tx::v1beta1::TxRaw, traits::Message};
+use cosmwasm_std::HexBinary;
+use k256::ecdsa::{Signature, SigningKey, signature::Signer as _};
+use ripemd::Ripemd160;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+use zeroize::Zeroizing;
+
+use crate::error::{AmburError, ErrorCode};
+use crate::tx::EncodedSignDoc;
+
+// Archway accounts use the cosmos coin type (118)
+pub static ARCHWAY_HD_PATH: &str = "m/44'/118'/0'/0/0";
+pub static MNEMONIC_ENV: &str = "AMBUR_MNEMONIC";
+pub static KEYFILE_PASSPHRASE_ENV: &str = "AMBUR_KEYFILE_PASSPHRASE";
+
+static KEYFILE_VERSION: u8 = 1;
+static KEYFILE_KDF: &str = "scrypt";
+
+// A 32 byte secp256k1 private key, encrypted with ChaCha20-Poly1305 under a key
+// derived from the passphrase with scrypt
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct Keyfile {
+    version: u8,
+    kdf: String,
+    log_n: u8,
+    r: u32,
+    p: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SignedTx {
+    // The sign doc that was signed, for review
+    pub sign_doc: EncodedSignDoc,
+    // base64 encoded protobuf `cosmos.tx.v1beta1.TxRaw`, ready to be broadcast
+    pub tx_bytes: String,
+    // upper case hex encoded SHA-256 hash of `tx_bytes`
+    pub tx_hash: String,
+}
+
+// The signing key never leaves this struct: it isn't serializable, and its
+// Debug output only shows the public key
+#[derive(Clone)]
+pub struct Signer {
+    key: SigningKey,
+    public_key: Vec<u8>,
+}
+
+impl fmt::Debug for Signer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Signer")
+            .field("public_key", &self.public_key())
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for Signer {
+    fn eq(&self, other: &Self) -> bool {
+        self.public_key == other.public_key
+    }
+}
+
+fn invalid_key(message: impl Into<String>) -> AmburError {
+    AmburError::new(ErrorCode::InvalidKey, message)
+}
+
+fn secret_env(name: &str) -> Result<Zeroizing<String>, AmburError> {
+    std::env::var(name)
+        .map(Zeroizing::new)
+        .map_err(|_| invalid_key(format!("The {name} environment variable isn't set")))
+}
+
+fn scrypt_key(
+    passphrase: &str,
+    salt: &[u8],
+    params: &scrypt::Params,
+) -> Result<Zeroizing<[u8; 32]>, AmburError> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    scrypt::scrypt(passphrase.as_bytes(), salt, params, key.as_mut())
+        .map_err(|_| invalid_key("Unable to derive the keyfile encryption key"))?;
+    Ok(key)
+}
+
+impl Signer {
+    fn from_key(key: SigningKey) -> Self {
+        let public_key = key
+            .verifying_key()
+            .to_encoded_point(true)
+            .as_bytes()
+            .to_vec();
+        Self { key, public_key }
+    }
+
+    pub fn from_mnemonic(phrase: &str, hd_path: &str) -> Result<Self, AmburError> {
+        // Errors must not echo the phrase back
+        let mnemonic =
+            Mnemonic::parse(phrase).map_err(|_| invalid_key("Invalid BIP-39 mnemonic phrase"))?;
+        let path = DerivationPath::from_str(hd_path)
+            .map_err(|_| invalid_key(format!("Invalid HD path {hd_path}")))?;
+        let seed = Zeroizing::new(mnemonic.to_seed(""));
+        let xprv = XPrv::derive_from_path(seed.as_slice(), &path)
+            .map_err(|_| invalid_key(format!("Unable to derive a key for HD path {hd_path}")))?;
+        Ok(Self::from_key(xprv.private_key().clone()))
+    }
+
+    pub fn from_keyfile(path: &Path, passphrase: &str) -> Result<Self, AmburError> {
+        let content = fs::read_to_string(path).map_err(|err| {
+            invalid_key(format!("Unable to read keyfile {}: {err}", path.display()))
+        })?;
+        let keyfile: Keyfile = serde_json::from_str(&content)
+            .map_err(|err| invalid_key(format!("Invalid keyfile {}: {err}", path.display())))?;
+        if keyfile.version != KEYFILE_VERSION || keyfile.kdf != KEYFILE_KDF {
+            return Err(invalid_key(format!(
+                "Unsupported keyfile {}: version {} with kdf {}",
+                path.display(),
+                keyfile.version,
+                keyfile.kdf
+            )));
+        }
+        let decode = |field: &str, value: &str| {
+            STANDARD
+                .decode(value)
+                .map_err(|_| invalid_key(format!("Invalid base64 {field} in keyfile")))
+        };
+        let salt = decode("salt", &keyfile.salt)?;
+        let nonce = decode("nonce", &keyfile.nonce)?;
+        let ciphertext = decode("ciphertext", &keyfile.ciphertext)?;
+        let nonce: [u8; 12] = nonce
+            .try_into()
+            .map_err(|_| invalid_key("Invalid nonce length in keyfile"))?;
+        let params = scrypt::Params::new(keyfile.log_n, keyfile.r, keyfile.p, 32)
+            .map_err(|_| invalid_key("Invalid scrypt parameters in keyfile"))?;
+        let key = scrypt_key(passphrase, &salt, &params)?;
+        let plaintext = ChaCha20Poly1305::new(key.as_ref().into())
+            .decrypt(&Nonce::from(nonce), ciphertext.as_slice())
+            .map(Zeroizing::new)
+            .map_err(|_| invalid_key("Unable to decrypt keyfile, check the passphrase"))?;
+        let key = SigningKey::from_slice(&plaintext)
+            .map_err(|_| invalid_key("Keyfile doesn't hold a valid secp256k1 key"))?;
+        Ok(Self::from_key(key))
+    }
+
+    // Encrypt the signing key into a new keyfile, existing files are never overwritten
+    pub fn write_keyfile(&self, path: &Path, passphrase: &str) -> Result<(), AmburError> {
+        self.write_keyfile_with(path, passphrase, &scrypt::Params::recommended())
+    }
+
+    // Keyfiles record their scrypt parameters, so any can be read back
+    fn write_keyfile_with(
+        &self,
+        path: &Path,
+        passphrase: &str,
+        params: &scrypt::Params,
+    ) -> Result<(), AmburError> {
+        if passphrase.is_empty() {
+            return Err(invalid_key("The keyfile passphrase can't be empty"));
+        }
+        let mut salt = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let key = scrypt_key(passphrase, &salt, params)?;
+        let secret = Zeroizing::new(self.key.to_bytes());
+        let ciphertext = ChaCha20Poly1305::new(key.as_ref().into())
+            .encrypt(&nonce, &secret[..])
+            .map_err(|_| invalid_key("Unable to encrypt the signing key"))?;
+        let keyfile = Keyfile {
+            version: KEYFILE_VERSION,
+            kdf: KEYFILE_KDF.to_string(),
+            log_n: params.log_n(),
+            r: params.r(),
+            p: params.p(),
+            salt: STANDARD.encode(salt),
+            nonce: STANDARD.encode(nonce),
+            ciphertext: STANDARD.encode(ciphertext),
+        };
+        let serialized = serde_json::to_string_pretty(&keyfile).unwrap_or_default();
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        options
+            .open(path)
+            .and_then(|mut file| file.write_all(serialized.as_bytes()))
+            .map_err(|err| {
+                invalid_key(format!("Unable to write keyfile {}: {err}", path.display()))
+            })
+    }
+
+    // base64 encoded compressed secp256k1 public key
+    pub fn public_key(&self) -> String {
+        STANDARD.encode(&self.public_key)
+    }
+
+    pub fn address(&self, prefix: &str) -> Result<String, AmburError> {
+        let hrp = Hrp::parse(prefix).map_err(|err| {
+            AmburError::new(
+                ErrorCode::InvalidNetwork,
+                format!("Invalid bech32 prefix {prefix}: {err}"),
+            )
+        })?;
+        let hash = Ripemd160::digest(Sha256::digest(&self.public_key));
+        bech32::encode::<Bech32>(hrp, &hash).map_err(|err| {
+            AmburError::new(
+                ErrorCode::InvalidAddress,
+                format!("Unable to encode signer address: {err}"),
+            )
+        })
+    }
+
+    // Sign `sign_bytes` and assemble the TxRaw from the sign doc's body and auth info
+    pub fn sign(&self, sign_doc: EncodedSignDoc) -> Result<SignedTx, AmburError> {
+        let decode = |field: &str, value: &str| {
+            STANDARD.decode(value).map_err(|err| {
+                AmburError::new(
+                    ErrorCode::SigningFailed,
+                    format!("Invalid base64 {field} in sign doc: {err}"),
+                )
+            })
+        };
+        let sign_bytes = decode("sign_bytes", &sign_doc.sign_bytes)?;
+        let signature: Signature = self.key.try_sign(&sign_bytes).map_err(|_| {
+            AmburError::new(ErrorCode::SigningFailed, "Unable to sign the sign doc")
+        })?;
+        let tx_raw = TxRaw {
+            body_bytes: decode("body_bytes", &sign_doc.body_bytes)?,
+            auth_info_bytes: decode("auth_info_bytes", &sign_doc.auth_info_bytes)?,
+            signatures: vec![signature.to_bytes().to_vec()],
+        };
+        let tx_bytes = tx_raw.encode_to_vec();
+        Ok(SignedTx {
+            sign_doc,
+            tx_hash: HexBinary::from(Sha256::digest(&tx_bytes).to_vec())
+                .to_hex()
+                .to_uppercase(),
+            tx_bytes: STANDARD.encode(tx_bytes),
+        })
+    }
+}
+
+// Load the signing key from an encrypted keyfile, or from the mnemonic in the
+// environment when no keyfile is given
+pub fn load_signer(keyfile: Option<&Path>, hd_path: &str) -> Result<Signer, AmburError> {
+    match keyfile {
+        Some(path) => Signer::from_keyfile(path, &secret_env(KEYFILE_PASSPHRASE_ENV)?),
+        None => Signer::from_mnemonic(&secret_env(MNEMONIC_ENV)?, hd_path),
+    }
+}
+
+// Derive the signing key from the mnemonic in the environment and encrypt it
+// into a new keyfile
+pub fn create_keyfile(path: &Path, hd_path: &str) -> Result<Signer, AmburError> {
+    let signer = Signer::from_mnemonic(&secret_env(MNEMONIC_ENV)?, hd_path)?;
+    signer.write_keyfile(path, &secret_env(KEYFILE_PASSPHRASE_ENV)?)?;
+    Ok(signer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    static MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    // A keyfile path of its own in the temp dir, removed when dropped
+    struct TempKeyfile(PathBuf);
+
+    impl TempKeyfile {
+        fn new() -> Self {
+            Self(std::env::temp_dir().join(format!("ambur-keyfile-{}.json", uuid::Uuid::new_v4())))
+        }
+    }
+
+    impl Drop for TempKeyfile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    fn signer() -> Signer {
+        Signer::from_mnemonic(MNEMONIC, ARCHWAY_HD_PATH).unwrap()
+    }
+
+    // Cheap scrypt parameters, the recommended ones take minutes in debug builds
+    fn write(signer: &Signer, keyfile: &TempKeyfile, passphrase: &str) -> Result<(), AmburError> {
+        let params = scrypt::Params::new(10, 8, 1, 32).unwrap();
+        signer.write_keyfile_with(&keyfile.0, passphrase, &params)
+    }
+
+    #[test]
+    fn derives_the_cosmos_account_of_a_mnemonic() {
+        // The well known first account of the BIP-39 test mnemonic
+        assert_eq!(
+            signer().address("cosmos").unwrap(),
+            "cosmos19rl4cm2hmr8afy4kldpxz3fka4jguq0auqdal4"
+        );
+        assert!(signer().address("archway").unwrap().starts_with("archway1"));
+        let err = Signer::from_mnemonic("abandon abandon", ARCHWAY_HD_PATH).unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidKey);
+        assert!(!err.message.contains("abandon"));
+    }
+
+    #[test]
+    fn keyfile_round_trip() {
+        let keyfile = TempKeyfile::new();
+        let signer = signer();
+        write(&signer, &keyfile, "correct horse").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&keyfile.0).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        // The key is encrypted, not stored as is
+        let content = fs::read_to_string(&keyfile.0).unwrap();
+        assert!(!content.contains(&STANDARD.encode(signer.key.to_bytes())));
+
+        assert_eq!(
+            Signer::from_keyfile(&keyfile.0, "correct horse").unwrap(),
+            signer
+        );
+        let err = Signer::from_keyfile(&keyfile.0, "wrong horse").unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidKey);
+        assert!(err.message.contains("check the passphrase"));
+
+        // Existing keyfiles are never overwritten
+        assert!(write(&signer, &keyfile, "another").is_err());
+        assert_eq!(
+            Signer::from_keyfile(&keyfile.0, "correct horse").unwrap(),
+            signer
+        );
+    }
+
+    #[test]
+    fn refuses_tampered_keyfiles() {
+        let keyfile = TempKeyfile::new();
+        write(&signer(), &keyfile, "correct horse").unwrap();
+        let mut parsed: Keyfile =
+            serde_json::from_str(&fs::read_to_string(&keyfile.0).unwrap()).unwrap();
+        let mut ciphertext = STANDARD.decode(&parsed.ciphertext).unwrap();
+        ciphertext[0] ^= 1;
+        parsed.ciphertext = STANDARD.encode(ciphertext);
+        fs::write(&keyfile.0, serde_json::to_string(&parsed).unwrap()).unwrap();
+        assert!(Signer::from_keyfile(&keyfile.0, "correct horse").is_err());
+
+        parsed.version = KEYFILE_VERSION + 1;
+        fs::write(&keyfile.0, serde_json::to_string(&parsed).unwrap()).unwrap();
+        let err = Signer::from_keyfile(&keyfile.0, "correct horse").unwrap_err();
+        assert!(err.message.contains("Unsupported keyfile"));
+    }
+
+    #[test]
+    fn refuses_an_empty_passphrase() {
+        let keyfile = TempKeyfile::new();
+        assert!(signer().write_keyfile(&keyfile.0, "").is_err());
+        assert!(!keyfile.0.exists());
+    }
+}
@@ -0,0 +1,468 @@
+use base64::{Engine, engine::general_purpose::STANDARD};
+use cosmos_sdk_proto::{
+    Any,
+    cosmos::base::v1beta1::Coin as ProtoCoin,
+    cosmos::crypto::secp256k1::PubKey,
+    cosmos::tx::signing::v1beta1::SignMode as ProtoSignMode,
+    cosmos::tx::v1beta1::{
+        AuthInfo, Fee, ModeInfo, SignDoc, SignerInfo, TxBody,
+        mode_info::{Single, Sum},
+    },
+    cosmwasm::wasm::v1::MsgExecuteContract,
+    traits::Message,
+};
+use cosmwasm_std::{Coin, CosmosMsg, HexBinary, WasmMsg};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+use crate::address::address_prefix;
+use crate::error::{AmburError, ErrorCode};
+use crate::network::ArchwayNetwork;
+
+pub static MSG_EXECUTE_CONTRACT_TYPE_URL: &str = "/cosmwasm.wasm.v1.MsgExecuteContract";
+pub static MSG_EXECUTE_CONTRACT_AMINO_TYPE: &str = "wasm/MsgExecuteContract";
+pub static SECP256K1_PUBKEY_TYPE_URL: &str = "/cosmos.crypto.secp256k1.PubKey";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SignMode {
+    Direct,
+    AminoJson,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SignDocRequest {
+    #[schemars(description = "sign mode of the sign doc (\"direct\" or \"amino_json\")")]
+    pub sign_mode: SignMode,
+    #[schemars(
+        description = "network the transaction will be broadcast to (e.g. \"Mainnet\" or \"Constantine\"), used for the chain-id"
+    )]
+    pub network: ArchwayNetwork,
+    #[schemars(description = "sender (signer) address of the transaction")]
+    pub sender: String,
+    #[schemars(
+        description = "ordered list of JSON stringified CosmosMsg values (e.g. the 'cosmos_msg' field returned by the build_*_execute_msg tools)"
+    )]
+    pub cosmos_msgs: Vec<String>,
+    #[schemars(description = "account number of the sender")]
+    pub account_number: u64,
+    #[schemars(description = "sequence (nonce) of the sender's account")]
+    pub sequence: u64,
+    #[schemars(description = "fee amount, in the smallest unit of the fee denom")]
+    pub fee_amount: String,
+    #[schemars(
+        description = "Optionally include the fee denom (defaults to the network's fee denom)"
+    )]
+    pub fee_denom: Option<String>,
+    #[schemars(description = "gas limit of the transaction")]
+    pub gas_limit: u64,
+    #[schemars(description = "Optionally include a memo for the transaction")]
+    pub memo: Option<String>,
+    #[schemars(
+        description = "Optionally include a block height after which the transaction is no longer valid"
+    )]
+    pub timeout_height: Option<u64>,
+    #[schemars(
+        description = "Optionally include the signer's base64 encoded secp256k1 public key (required if the account has never signed a transaction)"
+    )]
+    pub public_key: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SignDocParams {
+    pub chain_id: String,
+    pub account_number: u64,
+    pub sequence: u64,
+    pub fee: Vec<Coin>,
+    pub gas_limit: u64,
+    pub memo: String,
+    pub timeout_height: u64,
+    // base64 encoded compressed secp256k1 public key of the signer
+    pub public_key: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EncodedSignDoc {
+    pub sign_mode: SignMode,
+    // JSON representation of the sign doc, for review
+    pub sign_doc: Value,
+    // base64 encoded bytes the signer signs
+    pub sign_bytes: String,
+    // hex encoded SHA-256 hash of `sign_bytes`
+    pub sign_bytes_sha256: String,
+    // base64 encoded protobuf TxBody and AuthInfo of the transaction
+    pub body_bytes: String,
+    pub auth_info_bytes: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EncodedTxBody {
+    // base64 encoded protobuf `cosmos.tx.v1beta1.TxBody` bytes
+    pub tx_body_bytes: String,
+    // JSON (proto3 JSON mapping) equivalent of `tx_body_bytes`
+    pub tx_body: Value,
+}
+
+pub fn msg_execute_contract(
+    sender: &str,
+    cosmos_msg: &CosmosMsg,
+) -> Result<(Any, Value), AmburError> {
+    let (contract_addr, msg, funds) = wasm_execute(cosmos_msg)?;
+    let proto = MsgExecuteContract {
+        sender: sender.to_string(),
+        contract: contract_addr.clone(),
+        msg: msg.to_vec(),
+        funds: funds
+            .iter()
+            .map(|coin| ProtoCoin {
+                denom: coin.denom.clone(),
+                amount: coin.amount.to_string(),
+            })
+            .collect(),
+    };
+    let json = json!({
+        "@type": MSG_EXECUTE_CONTRACT_TYPE_URL,
+        "sender": sender,
+        "contract": contract_addr,
+        "msg": serde_json::from_slice::<Value>(msg).unwrap_or_default(),
+        "funds": funds,
+    });
+    let any = Any {
+        type_url: MSG_EXECUTE_CONTRACT_TYPE_URL.to_string(),
+        value: proto.encode_to_vec(),
+    };
+    Ok((any, json))
+}
+
+// Wrap wasm execute messages, in order, in an unsigned TxBody sent by `sender`
+pub fn build_tx_body(
+    sender: &str,
+    cosmos_msgs: &[CosmosMsg],
+    memo: &str,
+    timeout_height: u64,
+) -> Result<EncodedTxBody, AmburError> {
+    address_prefix(sender)?;
+    if cosmos_msgs.is_empty() {
+        return Err(AmburError::new(
+            ErrorCode::InvalidMessage,
+            "A transaction needs at least one message",
+        ));
+    }
+    let (messages, messages_json): (Vec<Any>, Vec<Value>) = cosmos_msgs
+        .iter()
+        .map(|cosmos_msg| msg_execute_contract(sender, cosmos_msg))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .unzip();
+    let tx_body = TxBody {
+        messages,
+        memo: memo.to_string(),
+        timeout_height,
+        ..Default::default()
+    };
+    Ok(EncodedTxBody {
+        tx_body_bytes: STANDARD.encode(tx_body.encode_to_vec()),
+        tx_body: json!({
+            "messages": messages_json,
+            "memo": memo,
+            "timeout_height": timeout_height.to_string(),
+            "extension_options": [],
+            "non_critical_extension_options": [],
+        }),
+    })
+}
+
+fn wasm_execute(cosmos_msg: &CosmosMsg) -> Result<(&String, &[u8], &Vec<Coin>), AmburError> {
+    match cosmos_msg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr,
+            msg,
+            funds,
+        }) => Ok((contract_addr, msg.as_slice(), funds)),
+        _ => Err(AmburError::new(
+            ErrorCode::InvalidMessage,
+            "Only wasm execute messages are supported in transactions",
+        )),
+    }
+}
+
+fn amino_msg_execute_contract(sender: &str, cosmos_msg: &CosmosMsg) -> Result<Value, AmburError> {
+    let (contract_addr, msg, funds) = wasm_execute(cosmos_msg)?;
+    Ok(json!({
+        "type": MSG_EXECUTE_CONTRACT_AMINO_TYPE,
+        "value": {
+            "sender": sender,
+            "contract": contract_addr,
+            "msg": serde_json::from_slice::<Value>(msg).unwrap_or_default(),
+            "funds": funds,
+        },
+    }))
+}
+
+// Amino JSON sign bytes are the sign doc with sorted keys, no insignificant
+// whitespace, and `&`, `<` and `>` escaped as in Go's encoding/json
+fn canonical_json(value: &Value) -> String {
+    fn sorted(value: &Value) -> Value {
+        match value {
+            Value::Object(map) => {
+                let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+                entries.sort_by(|a, b| a.0.cmp(b.0));
+                Value::Object(
+                    entries
+                        .into_iter()
+                        .map(|(key, value)| (key.clone(), sorted(value)))
+                        .collect(),
+                )
+            }
+            Value::Array(items) => Value::Array(items.iter().map(sorted).collect()),
+            value => value.clone(),
+        }
+    }
+    serde_json::to_string(&sorted(value))
+        .unwrap_or_default()
+        .replace('&', "\\u0026")
+        .replace('<', "\\u003c")
+        .replace('>', "\\u003e")
+}
+
+fn encoded_sign_doc(
+    sign_mode: SignMode,
+    sign_doc: Value,
+    sign_bytes: Vec<u8>,
+    body_bytes: Vec<u8>,
+    auth_info_bytes: Vec<u8>,
+) -> EncodedSignDoc {
+    EncodedSignDoc {
+        sign_mode,
+        sign_doc,
+        sign_bytes_sha256: HexBinary::from(Sha256::digest(&sign_bytes).to_vec()).to_hex(),
+        sign_bytes: STANDARD.encode(sign_bytes),
+        body_bytes: STANDARD.encode(body_bytes),
+        auth_info_bytes: STANDARD.encode(auth_info_bytes),
+    }
+}
+
+// Signer info and fee of the transaction, `mode` is the sign mode the
+// signature will be checked against
+fn auth_info(params: &SignDocParams, mode: ProtoSignMode) -> Result<AuthInfo, AmburError> {
+    let public_key = match &params.public_key {
+        Some(public_key) => {
+            let key = STANDARD.decode(public_key).map_err(|err| {
+                AmburError::new(
+                    ErrorCode::InvalidMessage,
+                    format!("Invalid base64 public key: {err}"),
+                )
+            })?;
+            Some(Any {
+                type_url: SECP256K1_PUBKEY_TYPE_URL.to_string(),
+                value: PubKey { key }.encode_to_vec(),
+            })
+        }
+        None => None,
+    };
+    Ok(AuthInfo {
+        signer_infos: vec![SignerInfo {
+            public_key,
+            mode_info: Some(ModeInfo {
+                sum: Some(Sum::Single(Single { mode: mode as i32 })),
+            }),
+            sequence: params.sequence,
+        }],
+        fee: Some(Fee {
+            amount: params
+                .fee
+                .iter()
+                .map(|coin| ProtoCoin {
+                    denom: coin.denom.clone(),
+                    amount: coin.amount.to_string(),
+                })
+                .collect(),
+            gas_limit: params.gas_limit,
+            payer: String::new(),
+            granter: String::new(),
+        }),
+        ..Default::default()
+    })
+}
+
+// Both sign modes return the TxBody and AuthInfo bytes, so a signature over
+// `sign_bytes` can be assembled into a TxRaw
+pub fn build_sign_doc(
+    sign_mode: SignMode,
+    sender: &str,
+    cosmos_msgs: &[CosmosMsg],
+    params: &SignDocParams,
+) -> Result<EncodedSignDoc, AmburError> {
+    let fee_json: Vec<Value> = params
+        .fee
+        .iter()
+        .map(|coin| json!({ "amount": coin.amount.to_string(), "denom": coin.denom }))
+        .collect();
+    let tx_body = build_tx_body(sender, cosmos_msgs, &params.memo, params.timeout_height)?;
+    let body_bytes = STANDARD.decode(&tx_body.tx_body_bytes).unwrap_or_default();
+    match sign_mode {
+        SignMode::AminoJson => {
+            let msgs = cosmos_msgs
+                .iter()
+                .map(|cosmos_msg| amino_msg_execute_contract(sender, cosmos_msg))
+                .collect::<Result<Vec<Value>, _>>()?;
+            let mut sign_doc = json!({
+                "account_number": params.account_number.to_string(),
+                "chain_id": params.chain_id,
+                "fee": {
+                    "amount": fee_json,
+                    "gas": params.gas_limit.to_string(),
+                },
+                "memo": params.memo,
+                "msgs": msgs,
+                "sequence": params.sequence.to_string(),
+            });
+            if params.timeout_height > 0 {
+                sign_doc["timeout_height"] = json!(params.timeout_height.to_string());
+            }
+            let sign_bytes = canonical_json(&sign_doc).into_bytes();
+            let auth_info = auth_info(params, ProtoSignMode::LegacyAminoJson)?;
+            Ok(encoded_sign_doc(
+                sign_mode,
+                sign_doc,
+                sign_bytes,
+                body_bytes,
+                auth_info.encode_to_vec(),
+            ))
+        }
+        SignMode::Direct => {
+            let auth_info_bytes = auth_info(params, ProtoSignMode::Direct)?.encode_to_vec();
+            let sign_doc = SignDoc {
+                body_bytes: body_bytes.clone(),
+                auth_info_bytes: auth_info_bytes.clone(),
+                chain_id: params.chain_id.clone(),
+                account_number: params.account_number,
+            };
+            let sign_doc_json = json!({
+                "body": tx_body.tx_body,
+                "auth_info": {
+                    "signer_infos": [{
+                        "public_key": params.public_key.as_ref().map(|key| json!({
+                            "@type": SECP256K1_PUBKEY_TYPE_URL,
+                            "key": key,
+                        })),
+                        "mode_info": { "single": { "mode": "SIGN_MODE_DIRECT" } },
+                        "sequence": params.sequence.to_string(),
+                    }],
+                    "fee": {
+                        "amount": fee_json,
+                        "gas_limit": params.gas_limit.to_string(),
+                        "payer": "",
+                        "granter": "",
+                    },
+                },
+                "chain_id": params.chain_id,
+                "account_number": params.account_number.to_string(),
+            });
+            Ok(encoded_sign_doc(
+                sign_mode,
+                sign_doc_json,
+                sign_doc.encode_to_vec(),
+                body_bytes,
+                auth_info_bytes,
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::Binary;
+
+    // Golden values were produced by an independent protobuf / amino JSON encoder
+    static SENDER: &str = "archway1tq5un2mjxdz5pv0c4vtfkml7wvlma4mnvxlwedrect4w9q4wff3qvqlsha";
+    static CONTRACT: &str = "archway16cv0gsnmdhptf6jt3llt5h7jkg5gd4gzh4vw569luc86yt0et6cssdfgzp";
+    static PUBLIC_KEY: &str = "AgECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8g";
+
+    // Unsorted keys, nested objects and arrays, and characters Go escapes
+    fn cosmos_msg() -> CosmosMsg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: CONTRACT.to_string(),
+            msg: Binary::from(br#"{"update":{"z":[{"b":2,"a":1}],"a":"x<&>y"}}"#),
+            funds: vec![Coin::new(1000u128, "uusdc")],
+        })
+    }
+
+    fn params(timeout_height: u64) -> SignDocParams {
+        SignDocParams {
+            chain_id: "archway-1".to_string(),
+            account_number: 42,
+            sequence: 7,
+            fee: vec![Coin::new(140000000000000000u128, "aarch")],
+            gas_limit: 200000,
+            memo: "a<b & c>d".to_string(),
+            timeout_height,
+            public_key: Some(PUBLIC_KEY.to_string()),
+        }
+    }
+
+    #[test]
+    fn canonical_json_sorts_keys_without_whitespace() {
+        let value = json!({ "b": [{ "d": 1, "c": [true, null] }], "a": { "z": "<&>", "y": {} } });
+        assert_eq!(
+            canonical_json(&value),
+            r#"{"a":{"y":{},"z":"\u003c\u0026\u003e"},"b":[{"c":[true,null],"d":1}]}"#
+        );
+    }
+
+    #[test]
+    fn amino_json_sign_bytes() {
+        let doc =
+            build_sign_doc(SignMode::AminoJson, SENDER, &[cosmos_msg()], &params(123)).unwrap();
+        let expected = concat!(
+            r#"{"account_number":"42","chain_id":"archway-1","#,
+            r#""fee":{"amount":[{"amount":"140000000000000000","denom":"aarch"}],"gas":"200000"},"#,
+            r#""memo":"a\u003cb \u0026 c\u003ed","#,
+            r#""msgs":[{"type":"wasm/MsgExecuteContract","value":{"#,
+            r#""contract":"archway16cv0gsnmdhptf6jt3llt5h7jkg5gd4gzh4vw569luc86yt0et6cssdfgzp","#,
+            r#""funds":[{"amount":"1000","denom":"uusdc"}],"#,
+            r#""msg":{"update":{"a":"x\u003c\u0026\u003ey","z":[{"a":1,"b":2}]}},"#,
+            r#""sender":"archway1tq5un2mjxdz5pv0c4vtfkml7wvlma4mnvxlwedrect4w9q4wff3qvqlsha"}}],"#,
+            r#""sequence":"7","timeout_height":"123"}"#,
+        );
+        assert_eq!(
+            STANDARD.decode(&doc.sign_bytes).unwrap(),
+            expected.as_bytes()
+        );
+        assert_eq!(
+            doc.sign_bytes_sha256,
+            "fbdb63282066b974c6baa1e8ca8d844499ea9794eed1f850fed5bf9aeeb30e3d"
+        );
+        assert_eq!(
+            doc.auth_info_bytes,
+            concat!(
+                "ClAKRgofL2Nvc21vcy5jcnlwdG8uc2VjcDI1NmsxLlB1YktleRIjCiECAQIDBAUGBwgJCgsMDQ4PEBES",
+                "ExQVFhcYGRobHB0eHyASBAoCCH8YBxIhChsKBWFhcmNoEhIxNDAwMDAwMDAwMDAwMDAwMDAQwJoM",
+            )
+        );
+    }
+
+    #[test]
+    fn direct_sign_bytes() {
+        let doc = build_sign_doc(SignMode::Direct, SENDER, &[cosmos_msg()], &params(0)).unwrap();
+        assert_eq!(
+            doc.sign_bytes,
+            concat!(
+                "CvwBCu4BCiQvY29zbXdhc20ud2FzbS52MS5Nc2dFeGVjdXRlQ29udHJhY3QSxQEKQmFyY2h3YXkxdHE1",
+                "dW4ybWp4ZHo1cHYwYzR2dGZrbWw3d3ZsbWE0bW52eGx3ZWRyZWN0NHc5cTR3ZmYzcXZxbHNoYRJCYXJj",
+                "aHdheTE2Y3YwZ3NubWRocHRmNmp0M2xsdDVoN2prZzVnZDRnemg0dnc1NjlsdWM4Nnl0MGV0NmNzc2Rm",
+                "Z3pwGix7InVwZGF0ZSI6eyJ6IjpbeyJiIjoyLCJhIjoxfV0sImEiOiJ4PCY+eSJ9fSoNCgV1dXNkYxIE",
+                "MTAwMBIJYTxiICYgYz5kEnUKUApGCh8vY29zbW9zLmNyeXB0by5zZWNwMjU2azEuUHViS2V5EiMKIQIB",
+                "AgMEBQYHCAkKCwwNDg8QERITFBUWFxgZGhscHR4fIBIECgIIARgHEiEKGwoFYWFyY2gSEjE0MDAwMDAw",
+                "MDAwMDAwMDAwMBDAmgwaCWFyY2h3YXktMSAq",
+            )
+        );
+        assert_eq!(
+            doc.sign_bytes_sha256,
+            "308c046da52758b7b8069227c0c6931a83f5a97114038bc3ef1dc5d9c3e2f072"
+        );
+    }
+}
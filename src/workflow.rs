@@ -0,0 +1,368 @@
+use cosmwasm_std::Uint128;
+use cw_utils::Expiration;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use crate::error::{AmburError, ErrorCode};
+use crate::execute::{ValidatedBundle, rewrite_variant_field};
+use crate::expiration::{rfc3339, to_datetime};
+use crate::family::CollectionName;
+use crate::network::ArchwayNetwork;
+use crate::payment::{PaymentTokenConfig, SwapPaymentToken, parse_payment_amount, split_amount};
+use crate::registry::Registry;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PreparedTx {
+    // Plain language description of what signing the transaction will do
+    pub summary: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swap_id: Option<String>,
+    // The execute messages of the bundle as JSON, in transaction order, for review
+    pub execute_msgs: Vec<Value>,
+    pub bundle: ValidatedBundle,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CollectionOfferRequest {
+    #[schemars(with = "CollectionName")]
+    pub nft: String,
+    #[schemars(description = "number of NFTs from the collection the offer is for")]
+    pub quantity: u32,
+    #[schemars(
+        description = "offered price per NFT in wUSDC (e.g. \"12.5 wUSDC\"; whole numbers need their symbol or a decimal point), provide either this or 'total_price'"
+    )]
+    pub price_per_nft: Option<String>,
+    #[schemars(
+        description = "offered price for all the NFTs in wUSDC (e.g. \"50 wUSDC\"; whole numbers need their symbol or a decimal point), provide either this or 'price_per_nft'"
+    )]
+    pub total_price: Option<String>,
+    #[schemars(
+        description = "Optionally include when the offer expires (e.g. \"in 7 days\", \"2026-12-31T18:00:00Z\", \"height 1234567\" or a JSON Expiration, see 'build_expiration'), defaults to never expiring"
+    )]
+    pub expires: Option<String>,
+    #[schemars(description = "network to make the offer on (e.g. \"Mainnet\")")]
+    pub network: ArchwayNetwork,
+    #[schemars(
+        description = "Optionally include the offerer (signer) address to also encode the messages as an unsigned TxBody"
+    )]
+    pub sender: Option<String>,
+}
+
+// The fields of a marketplace DetailsResponse that the workflows rely on
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SwapDetails {
+    pub creator: String,
+    #[serde(alias = "cw721")]
+    pub contract: Option<String>,
+    pub token_id: Option<String>,
+    pub price: Uint128,
+    pub payment_token: SwapPaymentToken,
+    pub swap_type: String,
+    pub expires: Option<Expiration>,
+}
+
+// Ids of the swaps in the tool documentation's examples, which must never be
+// reused for a new swap
+pub static EXAMPLE_SWAP_IDS: [&str; 2] = [
+    "cca4e046-97ba-45b2-841b-9adca039545e",
+    "9fb7be1f-0a25-451a-bc5e-31b13d9b850b",
+];
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GeneratedSwapId {
+    pub swap_id: String,
+    // "random" (v4) or "deterministic" (v5) UUID
+    pub kind: String,
+    // Whether the marketplace's `details` query confirmed the id is unused
+    pub checked: bool,
+}
+
+pub fn new_swap_id() -> String {
+    Uuid::new_v4().to_string()
+}
+
+// The same seller, cw721, token id (omitted for collection offers) and nonce
+// always give the same id, so a retried workflow can't create a second swap
+pub fn deterministic_swap_id(
+    seller: &str,
+    cw721: &str,
+    token_id: Option<&str>,
+    nonce: &str,
+) -> String {
+    let namespace = Uuid::new_v5(&Uuid::NAMESPACE_URL, b"https://ambur.art/swaps");
+    let name = format!("{seller}/{cw721}/{}/{nonce}", token_id.unwrap_or_default());
+    Uuid::new_v5(&namespace, name.as_bytes()).to_string()
+}
+
+// Give a `create` or `create_collection_offer` message without an id a new
+// random one
+pub fn fill_swap_id(execute_msg: &str) -> Result<String, AmburError> {
+    let creates = serde_json::from_str::<Value>(execute_msg)
+        .ok()
+        .and_then(|msg| {
+            msg.as_object().map(|msg| {
+                msg.contains_key("create") || msg.contains_key("create_collection_offer")
+            })
+        })
+        .unwrap_or_default();
+    if !creates {
+        return Ok(execute_msg.to_string());
+    }
+    rewrite_variant_field(execute_msg, "id", |fields| match fields.get("id") {
+        None | Some(Value::Null) => Ok(Some(Value::String(new_swap_id()))),
+        Some(Value::String(id)) if id.trim().is_empty() => Ok(Some(Value::String(new_swap_id()))),
+        _ => Ok(None),
+    })
+}
+
+// Parse a positive human price in the workflow's payment token, with or without
+// its symbol (e.g. "12.5 USDC" or "12.5"), with the same parser as message
+// prices. Prices in another registered token are refused, with native tokens
+// called out for cw20 only workflows (e.g. Offers)
+pub fn parse_price(
+    registry: &Registry,
+    price: &str,
+    config: &PaymentTokenConfig,
+    priced: &str,
+) -> Result<Uint128, AmburError> {
+    let (_, symbol) = split_amount(price)?;
+    if let Some(symbol) = symbol
+        && !symbol.eq_ignore_ascii_case(&config.symbol)
+    {
+        let message = match (
+            registry.payment_token(symbol, Some(&config.network)),
+            &config.token,
+        ) {
+            (Ok(other), SwapPaymentToken::Cw20 { .. })
+                if matches!(other.token, SwapPaymentToken::Native { .. }) =>
+            {
+                format!(
+                    "Invalid price {price}, {priced} can't be paid with native {}, they're paid with the {} cw20 token (e.g. \"12.5 {}\")",
+                    other.symbol, config.symbol, config.symbol
+                )
+            }
+            _ => format!(
+                "Invalid price {price}, {priced} are priced in {}",
+                config.symbol
+            ),
+        };
+        return Err(AmburError::new(ErrorCode::InvalidAmount, message));
+    }
+    let (amount, _) = parse_payment_amount(
+        registry,
+        price,
+        Some(&config.network),
+        Some(&config.token),
+        true,
+    )?;
+    if amount.is_zero() {
+        return Err(AmburError::new(
+            ErrorCode::InvalidAmount,
+            "The price must be greater than zero",
+        ));
+    }
+    Ok(amount)
+}
+
+// Total price of a collection offer for `quantity` NFTs, from exactly one of
+// the price per NFT or the total price
+pub fn collection_offer_total(
+    registry: &Registry,
+    config: &PaymentTokenConfig,
+    quantity: u32,
+    price_per_nft: Option<String>,
+    total_price: Option<String>,
+) -> Result<Uint128, AmburError> {
+    if quantity == 0 {
+        return Err(AmburError::new(
+            ErrorCode::InvalidAmount,
+            "A collection offer must be for at least 1 NFT",
+        ));
+    }
+    match (price_per_nft, total_price) {
+        (Some(price), None) => parse_price(registry, &price, config, "offers")?
+            .checked_mul(Uint128::from(quantity))
+            .map_err(|err| AmburError::new(ErrorCode::InvalidAmount, err.to_string())),
+        (None, Some(price)) => parse_price(registry, &price, config, "offers"),
+        _ => Err(AmburError::new(
+            ErrorCode::InvalidAmount,
+            "Provide exactly one of price_per_nft or total_price",
+        )),
+    }
+}
+
+// Human amount of registered payment tokens, base units otherwise
+pub fn describe_payment(
+    registry: &Registry,
+    network: &ArchwayNetwork,
+    amount: Uint128,
+    payment_token: &SwapPaymentToken,
+) -> String {
+    match registry.payment_token(payment_token.id(), Some(network)) {
+        Ok(config) => config.format(amount),
+        Err(_) => match payment_token {
+            SwapPaymentToken::Native { denom } => format!("{amount} {denom}"),
+            SwapPaymentToken::Cw20 { address } => format!("{amount} of cw20 token {address}"),
+        },
+    }
+}
+
+pub fn describe_expiration(expires: &Expiration) -> String {
+    match expires {
+        Expiration::Never {} => "never expires".to_string(),
+        Expiration::AtHeight(height) => format!("expires at block height {height}"),
+        Expiration::AtTime(time) => match to_datetime(*time) {
+            Some(time) => format!("expires at {}", rfc3339(time)),
+            None => format!("expires at unix time {}", time.seconds()),
+        },
+    }
+}
+
+// cw721 approvals that never expire are sent without an expiration
+pub fn approve_msg(spender: &str, token_id: &str, expires: &Expiration) -> Value {
+    let expires = match expires {
+        Expiration::Never {} => None,
+        expires => Some(expires),
+    };
+    json!({
+        "approve": {
+            "spender": spender,
+            "token_id": token_id,
+            "expires": expires,
+        }
+    })
+}
+
+pub fn create_swap_msg(
+    id: &str,
+    cw721: &str,
+    token_id: &str,
+    payment_token: &SwapPaymentToken,
+    price: Uint128,
+    swap_type: &str,
+    expires: &Expiration,
+) -> Value {
+    json!({
+        "create": {
+            "id": id,
+            "cw721": cw721,
+            "token_id": token_id,
+            "payment_token": payment_token,
+            "price": price,
+            "swap_type": swap_type,
+            "expires": expires,
+        }
+    })
+}
+
+// `price` is the total for the whole batch of NFTs, not the price per NFT
+pub fn create_collection_offer_msg(
+    id: &str,
+    cw721: &str,
+    payment_token: &SwapPaymentToken,
+    price: Uint128,
+    quantity: u32,
+    expires: &Expiration,
+) -> Value {
+    json!({
+        "create_collection_offer": {
+            "id": id,
+            "cw721": cw721,
+            "payment_token": payment_token,
+            "price": price,
+            "quantity": quantity,
+            "expires": expires,
+        }
+    })
+}
+
+pub fn finish_swap_msg(id: &str) -> Value {
+    json!({ "finish": { "id": id } })
+}
+
+pub fn increase_allowance_msg(spender: &str, amount: Uint128) -> Value {
+    json!({
+        "increase_allowance": {
+            "spender": spender,
+            "amount": amount,
+            "expires": null,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::payment::{offer_payment_token, sale_payment_token};
+
+    #[test]
+    fn collection_offers_need_a_quantity_and_one_price() {
+        let registry = Registry::default();
+        let wusdc = offer_payment_token(&registry, &ArchwayNetwork::Mainnet).unwrap();
+        let total = |quantity, price_per_nft: Option<&str>, total_price: Option<&str>| {
+            collection_offer_total(
+                &registry,
+                wusdc,
+                quantity,
+                price_per_nft.map(str::to_string),
+                total_price.map(str::to_string),
+            )
+        };
+        let err = total(0, Some("12.5 wUSDC"), None).unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidAmount);
+        assert!(err.message.contains("at least 1 NFT"), "{}", err.message);
+
+        assert_eq!(
+            total(4, Some("12.5 wUSDC"), None).unwrap(),
+            Uint128::new(50_000_000)
+        );
+        assert_eq!(
+            total(4, None, Some("50 wUSDC")).unwrap(),
+            Uint128::new(50_000_000)
+        );
+        for (price_per_nft, total_price) in [(None, None), (Some("12.5"), Some("50.0"))] {
+            let err = total(4, price_per_nft, total_price).unwrap_err();
+            assert!(err.message.contains("exactly one"), "{}", err.message);
+        }
+    }
+
+    #[test]
+    fn parse_price_refuses_other_payment_tokens() {
+        let registry = Registry::default();
+        let usdc = sale_payment_token(&registry, &ArchwayNetwork::Mainnet).unwrap();
+        let wusdc = offer_payment_token(&registry, &ArchwayNetwork::Mainnet).unwrap();
+
+        // Offers are paid in cw20 wUSDC, never in native USDC
+        let err = parse_price(&registry, "12.5 USDC", wusdc, "offers").unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidAmount);
+        assert!(
+            err.message.contains(
+                "offers can't be paid with native USDC, they're paid with the wUSDC cw20 token"
+            ),
+            "{}",
+            err.message
+        );
+        let err = parse_price(&registry, "12.5 wUSDC", usdc, "sale listings").unwrap_err();
+        assert!(
+            err.message.contains("sale listings are priced in USDC"),
+            "{}",
+            err.message
+        );
+
+        for price in ["12.5 wUSDC", "12.5 wusdc", "12.5"] {
+            assert_eq!(
+                parse_price(&registry, price, wusdc, "offers").unwrap(),
+                Uint128::new(12_500_000)
+            );
+        }
+        // Whole numbers without a symbol are ambiguous, and prices are positive
+        for price in ["12", "0 wUSDC", "0.0"] {
+            assert!(
+                parse_price(&registry, price, wusdc, "offers").is_err(),
+                "{price}"
+            );
+        }
+    }
+}